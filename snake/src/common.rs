@@ -19,9 +19,35 @@ pub const SNAKE_SIZE: f32 = 10f32;
 pub const SNAKE_DIMENSIONS: Vec2 = Vec2::splat(SNAKE_SIZE);
 /// The snake speed
 pub const SNAKE_SPEED_FACTOR: f32 = 270f32;
+/// The highest snake speed, at which it moves by its own size each tick.
+pub const SNAKE_MAX_SPEED_FACTOR: f32 = SNAKE_SIZE / SIMULATION_TIME_STEP;
+/// The number of bonuses eaten, and of seconds played, the speed of custom
+/// settings is compared at to rate their difficulty.
+pub const RATED_PROGRESS: u32 = 20;
+/// The distance between two queue members along the snake path.
+pub const QUEUE_SPACING: f32 = SNAKE_SIZE;
+/// The distance along the snake path under which a queue member is part of
+/// the neck, which always touches the head and can't be bitten.
+pub const NECK_LENGTH: f32 = 1.5f32 * SNAKE_SIZE;
+/// The overlap under which the head only touches a queue member instead of
+/// biting it, so the rounding errors on tight turns aren't deadly.
+pub const BITE_TOLERANCE: f32 = 0.01f32;
+/// The maximum number of turns the player can queue in advance.
+pub const INPUT_BUFFER_SIZE: usize = 3;
+
+/// The duration of a simulation tick, in seconds.
+pub const SIMULATION_TIME_STEP: f32 = 1f32 / 60f32;
+/// The maximum number of simulation ticks ran within a single frame,
+/// the late time is dropped beyond it so a slow frame can't freeze the game.
+pub const MAX_TICKS_PER_FRAME: u32 = 5;
 
 /// The bonus diameter
 pub const BONUS_DIAMETER: f32 = 10f32;
+/// The number of random positions tried for a bonus before looking for the
+/// free cells one by one.
+pub const BONUS_PLACEMENT_ATTEMPTS: u32 = 32;
+/// The default minimum distance between the snake head and a newly spawned bonus.
+pub const BONUS_MIN_HEAD_DISTANCE: f32 = 5f32 * SNAKE_SIZE;
 
 /// The size of each border
 pub const BORDER_SIZE: f32 = 15f32;
@@ -30,13 +56,106 @@ pub const BORDER_SIZE: f32 = 15f32;
 pub const NORMAL_BUTTON: Color = Color::DARK_GRAY;
 /// The button when the user hover it in initialized state.
 pub const HOVERED_BUTTON: Color = Color::GRAY;
+/// The button of the selected option in initialized state.
+pub const SELECTED_BUTTON: Color = Color::DARK_GREEN;
+
+/// The size of a portal, the snake goes through it once its head center is inside.
+pub const PORTAL_SIZE: f32 = 2f32 * SNAKE_SIZE;
+
+/// The size of the rooms of the generated mazes.
+pub const MAZE_CELL_SIZE: f32 = 8f32 * SNAKE_SIZE;
+/// The chance that each remaining wall of a generated maze is removed, so the
+/// snake can loop around.
+pub const MAZE_BRAIDING: f64 = 0.25f64;
+
+/// The shortest distance between the two snakes of a versus game when they spawn.
+pub const RIVAL_MIN_SPAWN_DISTANCE: f32 = 15f32 * SNAKE_SIZE;
+/// The number of snake lengths the second snake of a versus game looks ahead
+/// for open space, when it spawns and when it starts.
+pub const RIVAL_CLEAR_RUN: usize = 12;
 
-/// The chance that an extra bonus spawns as a percentage.
+/// The chance that an extra bonus spawns as a percentage, on the normal difficulty.
 pub const CHANCE_OF_EXTRA_BONUS: f64 = 0.10f64;
 /// The extra bonus color.
 pub const EXTRA_BONUS_RGB: (f32, f32, f32) = (202f32 / 256f32, 138f32 / 256f32, 4f32 / 265f32);
-/// The time to get the bonus for the user.
+/// The time to get the bonus for the user, on the normal difficulty.
 pub const TIME_FOR_BONUS: f32 = 10f32;
+/// The maximum number of extra bonuses on the screen, on the normal difficulty.
+pub const MAX_EXTRA_BONUSES: u32 = 2;
+
+/// The longest time between two eaten bonuses for them to be part of a combo, in seconds.
+pub const COMBO_WINDOW: f32 = 3f32;
+/// The time after a bonus appeared under which eating it rewards quickness points, in seconds.
+pub const QUICK_BONUS_TIME: f32 = 2f32;
+/// The quickness points rewarded for eating a bonus the moment it appeared.
+pub const QUICK_BONUS_POINTS: u32 = 3;
+
+/// The width of the HUD bar of a timed effect which just started.
+pub const EFFECT_INDICATOR_WIDTH: f32 = 120f32;
 
 /// The font name
 pub const FONT_ASSET_NAME: &str = "score_font.otf";
+
+/// The assets folder holding the levels
+pub const LEVELS_FOLDER: &str = "levels";
+/// The levels shipped with the game, for the platforms that can't list a folder
+pub const BUILTIN_LEVELS: [&str; 8] = [
+    "levels/screen.level.ron",
+    "levels/cross.level.ron",
+    "levels/horizontal.level.ron",
+    "levels/vertical.level.ron",
+    "levels/machinery.level.ron",
+    "levels/portals.level.ron",
+    "levels/mirror.level.ron",
+    "levels/rebound.level.ron",
+];
+
+/// The folder the levels made in the level editor are saved to, next to the
+/// other saved files rather than in the shipped assets.
+pub const CUSTOM_LEVELS_FOLDER: &str = "custom_levels";
+
+/// The file the replay of the last game is saved to.
+pub const REPLAY_FILE_NAME: &str = "last_replay.ron";
+
+/// The file the high scores are saved to, also their key in the browser local storage.
+pub const HIGH_SCORES_FILE_NAME: &str = "high_scores.ron";
+/// The number of scores kept for each level and difficulty.
+pub const HIGH_SCORES_COUNT: usize = 5;
+/// The name the high scores of every generated maze are kept under.
+pub const GENERATED_LEVELS_HIGH_SCORES_NAME: &str = "Maze";
+/// The maximum length of a name typed in the high scores.
+pub const HIGH_SCORE_NAME_MAX_LENGTH: usize = 12;
+/// The name used when the player doesn't type one.
+pub const DEFAULT_PLAYER_NAME: &str = "Player";
+
+/// The file the lifetime statistics are saved to, also their key in the
+/// browser local storage.
+pub const LIFETIME_STATS_FILE_NAME: &str = "lifetime_stats.ron";
+
+/// The file the unlocked achievements are saved to, also their key in the
+/// browser local storage.
+pub const ACHIEVEMENTS_FILE_NAME: &str = "achievements.ron";
+/// The time a notification of an unlocked achievement stays on the screen, in seconds.
+pub const ACHIEVEMENT_TOAST_DURATION: f32 = 3f32;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn builtin_levels_list_every_level_file() {
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(LEVELS_FOLDER);
+        for entry in std::fs::read_dir(folder).unwrap() {
+            let file_name = entry.unwrap().file_name();
+            let path = format!("{}/{}", LEVELS_FOLDER, file_name.to_string_lossy());
+            assert!(
+                BUILTIN_LEVELS.contains(&path.as_str()),
+                "{} isn't loaded by the browser build",
+                path
+            );
+        }
+    }
+}
@@ -1,9 +1,13 @@
-use super::{prelude::Collider, spawnable::Spawnable};
+use super::spawnable::Spawnable;
+use crate::common::*;
+use crate::world::prelude::TimedEffect;
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// A bonus once collided with the snake will increase its size, and thus the
-/// player's score.
-#[derive(Component, Default, Clone, Copy, Debug)]
+/// player's score, or give it a power-up.
+#[derive(Component, Default, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Bonus {
     /// A normal bonus only increase the player score by a single point.
     ///
@@ -14,16 +18,151 @@ pub enum Bonus {
     ///
     /// Extra bonuses are rare and appear randomly once a normal bonus is collided by the snake.
     ExtraBonus,
+    /// Removes a few members from the tail of the snake.
+    Shrink,
+    /// Slows the snake down for a while.
+    SlowMotion,
+    /// Speeds the snake up for a while, rewarding more points.
+    SpeedBoost,
+    /// Lets the snake go through the walls and the obstacles for a while.
+    Ghost,
+    /// Multiplies the points rewarded by the bonuses for a while.
+    ScoreMultiplier,
 }
 
+impl fmt::Display for Bonus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// What happens once a bonus is eaten, besides the snake growing by its points.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BonusEffect {
+    /// Nothing else happens.
+    None,
+    /// The given number of members are removed from the tail.
+    Shrink(u32),
+    /// The given effect applies to the snake for the given duration, in seconds.
+    Timed(TimedEffect, f32),
+}
+
+/// The shape a bonus is drawn with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BonusShape {
+    /// A circle.
+    Circle,
+    /// A regular polygon with the given number of sides.
+    Polygon(usize),
+}
+
+/// Everything defining a kind of bonus.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BonusSpec {
+    /// The points rewarded, which is also the number of members the snake grows by.
+    pub points: u32,
+    /// The weight of the bonus when picking which extra bonus spawns, the
+    /// bonuses with no weight never spawn as extra bonuses.
+    pub weight: u32,
+    /// The color of the bonus.
+    pub color: Color,
+    /// The shape of the bonus.
+    pub shape: BonusShape,
+    /// The time the bonus lies on the playfield before it expires, as a ratio
+    /// of the extra bonus lifetime of the difficulty, none if it never expires.
+    pub lifetime: Option<f32>,
+    /// What happens once the bonus is eaten.
+    pub effect: BonusEffect,
+}
+
+/// The identifier of the game world bonus rendered by the entity.
+#[derive(Component, Deref, DerefMut, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BonusId(pub u32);
+
 impl Bonus {
+    /// Every bonus that may spawn as an extra bonus.
+    pub const EXTRA: [Self; 6] = [
+        Self::ExtraBonus,
+        Self::Shrink,
+        Self::SlowMotion,
+        Self::SpeedBoost,
+        Self::Ghost,
+        Self::ScoreMultiplier,
+    ];
+
+    /// Returns the definition of the bonus.
+    pub fn spec(&self) -> BonusSpec {
+        match self {
+            Bonus::Normal => BonusSpec {
+                points: 1,
+                weight: 0,
+                color: Color::WHITE,
+                shape: BonusShape::Circle,
+                lifetime: None,
+                effect: BonusEffect::None,
+            },
+            Bonus::ExtraBonus => BonusSpec {
+                points: 5,
+                weight: 10,
+                color: Color::rgb(EXTRA_BONUS_RGB.0, EXTRA_BONUS_RGB.1, EXTRA_BONUS_RGB.2),
+                shape: BonusShape::Circle,
+                lifetime: Some(1f32),
+                effect: BonusEffect::None,
+            },
+            Bonus::Shrink => BonusSpec {
+                points: 0,
+                weight: 4,
+                color: Color::TOMATO,
+                shape: BonusShape::Polygon(3),
+                lifetime: Some(1.2),
+                effect: BonusEffect::Shrink(3),
+            },
+            Bonus::SlowMotion => BonusSpec {
+                points: 1,
+                weight: 4,
+                color: Color::CYAN,
+                shape: BonusShape::Polygon(4),
+                lifetime: Some(1f32),
+                effect: BonusEffect::Timed(TimedEffect::Speed(0.6), 5f32),
+            },
+            Bonus::SpeedBoost => BonusSpec {
+                points: 3,
+                weight: 3,
+                color: Color::ORANGE_RED,
+                shape: BonusShape::Polygon(5),
+                lifetime: Some(0.8),
+                effect: BonusEffect::Timed(TimedEffect::Speed(1.5), 5f32),
+            },
+            Bonus::Ghost => BonusSpec {
+                points: 1,
+                weight: 2,
+                color: Color::rgba(0.8, 0.8, 1f32, 1f32),
+                shape: BonusShape::Polygon(6),
+                lifetime: Some(0.6),
+                effect: BonusEffect::Timed(TimedEffect::Ghost, 4f32),
+            },
+            Bonus::ScoreMultiplier => BonusSpec {
+                points: 1,
+                weight: 2,
+                color: Color::FUCHSIA,
+                shape: BonusShape::Polygon(8),
+                lifetime: Some(0.7),
+                effect: BonusEffect::Timed(TimedEffect::ScoreMultiplier(2), 8f32),
+            },
+        }
+    }
+
     /// Returns the number of points that are rewarded for
     /// colliding with the bonus.
     pub fn get_points(&self) -> u32 {
-        match self {
-            Bonus::Normal => 1,
-            Bonus::ExtraBonus => 5,
-        }
+        self.spec().points
+    }
+
+    /// Returns the color of the bonus, faded by the given ratio of its lifetime left.
+    pub fn get_color(&self, time_left: f32) -> Color {
+        let mut color = self.spec().color;
+        color.set_a(color.a() * time_left);
+        color
     }
 }
 
@@ -34,19 +173,17 @@ impl Spawnable<MaterialMesh2dBundle<ColorMaterial>> for Bonus {
         materials: &mut ResMut<Assets<ColorMaterial>>,
         meshes: &mut ResMut<Assets<Mesh>>,
     ) -> MaterialMesh2dBundle<ColorMaterial> {
-        let bonus_color: Color = match self {
-            Bonus::Normal => Color::WHITE,
-            Bonus::ExtraBonus => Color::GOLD,
+        let mesh = match self.spec().shape {
+            BonusShape::Circle => Mesh::from(shape::Circle::default()),
+            BonusShape::Polygon(sides) => Mesh::from(shape::RegularPolygon::new(0.5, sides)),
         };
         MaterialMesh2dBundle {
-            mesh: meshes.add(Mesh::from(shape::Circle::default())).into(),
+            mesh: meshes.add(mesh).into(),
             transform,
-            material: materials.add(ColorMaterial::from(bonus_color)),
+            material: materials.add(ColorMaterial::from(self.spec().color)),
             ..default()
         }
     }
 
-    fn additional_systems(&self, commands: &mut bevy::ecs::system::EntityCommands) {
-        commands.insert(Collider);
-    }
+    fn additional_systems(&self, _commands: &mut bevy::ecs::system::EntityCommands) {}
 }
@@ -7,26 +7,112 @@ pub mod border {
     pub struct Border;
 }
 
-pub mod collider {
+pub mod bonus;
+
+pub mod obstacle {
     use bevy::prelude::*;
-    /// A collider is something the snake can't go through without triggering an event.
-    ///
-    /// It can either be a bonus, or a border.
+    /// Identifies the obstacle of the game world a rendered border is.
+    #[derive(Deref, DerefMut, Debug, Copy, Clone, Component)]
+    pub struct ObstacleId(pub usize);
+}
+
+pub mod level_button {
+    use crate::resources::prelude::Level;
+    use bevy::prelude::*;
+    /// A button of the level picker, telling which level it starts.
+    #[derive(Debug, Component)]
+    pub enum LevelButton {
+        /// A level loaded from the assets.
+        File(Handle<Level>),
+        /// A maze generated from the seed of the settings.
+        Generated,
+    }
+}
+
+pub mod difficulty_button {
+    use crate::resources::prelude::Difficulty;
+    use bevy::prelude::*;
+    /// A button of the difficulty picker, telling which difficulty it selects.
+    #[derive(Deref, Debug, Copy, Clone, Component)]
+    pub struct DifficultyButton(pub Difficulty);
+}
+
+pub mod achievement {
+    use bevy::prelude::*;
+    /// A notification telling an achievement has just been unlocked, along
+    /// with the time it has been shown for, in seconds.
+    #[derive(Deref, DerefMut, Debug, Default, Copy, Clone, Component)]
+    pub struct AchievementToast(pub f32);
+
+    /// The screen listing every achievement.
     #[derive(Debug, Default, Component)]
-    pub struct Collider;
+    pub struct AchievementGallery;
 }
 
-pub mod bonus;
+pub mod game_summary {
+    use bevy::prelude::*;
+    /// The screen summing up a game once it ended.
+    #[derive(Debug, Default, Component)]
+    pub struct GameSummary;
+
+    /// A part of the game summary, telling what its text shows.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+    pub enum SummaryText {
+        /// How the game ended.
+        Title,
+        /// The detail of the score.
+        Score,
+        /// The statistics of the game.
+        Game,
+        /// The statistics of every game played.
+        Lifetime,
+        /// The high scores of the level with the difficulty.
+        HighScores,
+        /// What the user can do next.
+        Prompt,
+    }
+}
+
+pub mod high_score_panel {
+    use bevy::prelude::*;
+    /// The text of the level picker showing the high scores of the hovered level.
+    #[derive(Debug, Default, Component)]
+    pub struct HighScorePanel;
+}
+
+pub mod effect_indicator {
+    use crate::world::prelude::TimedEffect;
+    use bevy::prelude::*;
+    /// A bar of the HUD showing the time left of the timed effect applying
+    /// to the snake at the given index.
+    #[derive(Debug, Copy, Clone, Component)]
+    pub struct EffectIndicator {
+        pub index: usize,
+        pub effect: TimedEffect,
+    }
+}
 
 pub mod spawnable;
 
 pub mod queue;
 
+pub mod portal;
+
+pub mod spawn_marker;
+
 pub mod prelude {
-    pub use super::bonus::Bonus;
+    pub use super::achievement::{AchievementGallery, AchievementToast};
+    pub use super::bonus::{Bonus, BonusEffect, BonusId, BonusShape, BonusSpec};
     pub use super::border::*;
-    pub use super::collider::*;
+    pub use super::difficulty_button::DifficultyButton;
+    pub use super::effect_indicator::EffectIndicator;
+    pub use super::game_summary::{GameSummary, SummaryText};
+    pub use super::high_score_panel::HighScorePanel;
+    pub use super::level_button::LevelButton;
+    pub use super::obstacle::ObstacleId;
+    pub use super::portal::Portal;
     pub use super::queue::*;
     pub use super::snake::*;
+    pub use super::spawn_marker::SpawnMarker;
     pub use super::spawnable::Spawnable;
 }
@@ -0,0 +1,39 @@
+use super::spawnable::Spawnable;
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+
+use crate::common::*;
+
+/// A portal of the playfield, both portals of a pair share the same color.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Portal {
+    /// The index of the pair the portal belongs to.
+    pub pair: usize,
+}
+
+impl Portal {
+    /// Returns the color of the portal pair, the hues of the pairs being spread
+    /// around the color wheel.
+    fn get_color(&self) -> Color {
+        Color::hsl((self.pair as f32 * 137.5f32 + 200f32) % 360f32, 0.8f32, 0.6f32)
+    }
+}
+
+impl Spawnable<MaterialMesh2dBundle<ColorMaterial>> for Portal {
+    fn get_bundle(
+        &self,
+        transform: Transform,
+        materials: &mut ResMut<Assets<ColorMaterial>>,
+        meshes: &mut ResMut<Assets<Mesh>>,
+    ) -> MaterialMesh2dBundle<ColorMaterial> {
+        MaterialMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(shape::Circle::new(PORTAL_SIZE / 2f32)))
+                .into(),
+            transform,
+            material: materials.add(ColorMaterial::from(self.get_color())),
+            ..default()
+        }
+    }
+
+    fn additional_systems(&self, _commands: &mut bevy::ecs::system::EntityCommands) {}
+}
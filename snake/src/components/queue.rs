@@ -1,18 +1,16 @@
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
 
 use crate::common::*;
+use crate::world::prelude::Player;
 
-use super::prelude::{Collider, Spawnable};
+use super::prelude::Spawnable;
 
 /// The queue grows as long as the snake eats bonuses.
+///
+/// Each rendered queue member holds this component, telling whose snake it
+/// belongs to.
 #[derive(Debug, Component, Default, Copy, Clone)]
-pub enum Queue {
-    /// First queue component, isn't collidable
-    First,
-    /// Others queue members
-    #[default]
-    Other
-}
+pub struct Queue(pub Player);
 
 impl Spawnable<MaterialMesh2dBundle<ColorMaterial>> for Queue {
     fn get_bundle(
@@ -29,15 +27,10 @@ impl Spawnable<MaterialMesh2dBundle<ColorMaterial>> for Queue {
                 }))
                 .into(),
             transform,
-            material: materials.add(ColorMaterial::from(Color::GRAY)),
+            material: materials.add(ColorMaterial::from(self.0.queue_color())),
             ..default()
         }
     }
 
-    fn additional_systems(&self, commands: &mut bevy::ecs::system::EntityCommands) {
-        match self {
-            Queue::First => {},
-            Queue::Other => {commands.insert(Collider);}
-        }
-    }
+    fn additional_systems(&self, _commands: &mut bevy::ecs::system::EntityCommands) {}
 }
@@ -0,0 +1,32 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+
+use crate::common::*;
+
+use super::prelude::Spawnable;
+
+/// Shows where the snake spawns in the level editor.
+#[derive(Debug, Component, Default, Copy, Clone)]
+pub struct SpawnMarker;
+
+impl Spawnable<MaterialMesh2dBundle<ColorMaterial>> for SpawnMarker {
+    fn get_bundle(
+        &self,
+        transform: Transform,
+        materials: &mut ResMut<Assets<ColorMaterial>>,
+        meshes: &mut ResMut<Assets<Mesh>>,
+    ) -> MaterialMesh2dBundle<ColorMaterial> {
+        MaterialMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(shape::Quad {
+                    size: SNAKE_DIMENSIONS,
+                    ..default()
+                }))
+                .into(),
+            transform,
+            material: materials.add(ColorMaterial::from(Color::LIME_GREEN)),
+            ..default()
+        }
+    }
+
+    fn additional_systems(&self, _commands: &mut bevy::ecs::system::EntityCommands) {}
+}
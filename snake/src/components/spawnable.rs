@@ -2,6 +2,7 @@ use bevy::{
     asset::Assets,
     ecs::bundle::Bundle,
     ecs::component::Component,
+    ecs::entity::Entity,
     ecs::system::{Commands, EntityCommands, ResMut},
     render::mesh::Mesh,
     sprite::ColorMaterial,
@@ -25,18 +26,19 @@ where
         meshes: &mut ResMut<Assets<Mesh>>,
     ) -> T;
 
-    /// Spawns the component.
+    /// Spawns the component, and returns the spawned entity.
     fn spawn(
         &self,
         position: Transform,
         commands: &mut Commands,
         materials: &mut ResMut<Assets<ColorMaterial>>,
         meshes: &mut ResMut<Assets<Mesh>>,
-    ) {
+    ) -> Entity {
         let bundle: T = Self::get_bundle(self, position, materials, meshes);
         let mut commands = commands.spawn();
         commands.insert(*self).insert_bundle(bundle);
         Self::additional_systems(self, &mut commands);
+        commands.id()
     }
 
     /// Additional systems, can be handful if for instance,
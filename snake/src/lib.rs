@@ -0,0 +1,5 @@
+pub mod common;
+pub mod components;
+pub mod resources;
+pub mod systems;
+pub mod world;
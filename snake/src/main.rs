@@ -1,51 +1,65 @@
-mod common;
-mod components;
-mod resources;
-mod systems;
+use snake::common::*;
+use snake::resources::prelude::*;
+use snake::systems::prelude::*;
+use snake::world::prelude::*;
 
-use common::*;
-use components::prelude::*;
-use resources::prelude::*;
-use systems::prelude::*;
-
-use rand::Rng;
-
-use bevy::{app::AppExit, prelude::*, time::Stopwatch};
+use bevy::{app::AppExit, prelude::*};
 
 #[derive(Debug, Component)]
 struct UserText;
 
-/// The event following a conflict of position between the snake and a collider.
-#[derive(Default)]
-pub enum CollisionEvent {
-    #[default]
-    Border,
-    Bonus(u32),
-}
-
-/// Timer for extra bonuses.
-#[derive(Clone, Deref, DerefMut, Default)]
-pub struct ExtraBonusTimer(Stopwatch);
-
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .insert_resource(ClearColor(Color::BLACK))
         .init_resource::<Score>()
-        .init_resource::<Option<BorderSet>>()
-        .init_resource::<ExtraBonusTimer>()
+        .init_resource::<GameSettings>()
+        .init_resource::<SimulationClock>()
+        .init_resource::<Option<Level>>()
+        .init_resource::<LevelHandles>()
+        .init_resource::<Option<LevelEditor>>()
+        .init_resource::<Option<Replay>>()
+        .init_resource::<HighScores>()
+        .init_resource::<LifetimeStats>()
+        .init_resource::<Achievements>()
         .init_resource::<AppFont>()
         .add_state::<GameState>(GameState::default())
-        .add_event::<CollisionEvent>()
+        .add_event::<GameEvent>()
+        .add_event::<AchievementUnlocked>()
+        .add_asset::<Level>()
+        .init_asset_loader::<LevelLoader>()
         .add_startup_system(setup)
         .add_startup_system(load_assets)
+        .add_startup_system(load_levels)
         .add_startup_system(window_resize_system)
+        .add_startup_system(load_last_replay)
+        .add_startup_system(load_high_scores)
+        .add_startup_system(load_lifetime_stats)
+        .add_startup_system(load_achievements)
         .add_system(update_text)
+        .add_system(spawn_achievement_toasts)
+        .add_system(fade_achievement_toasts)
         .add_system_set(
-            SystemSet::on_enter(GameState::Initialized).with_system(spawn_border_set_buttons),
+            SystemSet::on_update(GameState::Initialized)
+                .with_system(spawn_border_set_buttons)
+                .with_system(border_set_choose_system)
+                .with_system(movement_mode_toggle_system)
+                .with_system(speed_curve_toggle_system)
+                .with_system(speed_driver_toggle_system)
+                .with_system(custom_setting_system)
+                .with_system(wall_rule_toggle_system)
+                .with_system(play_mode_toggle_system)
+                .with_system(difficulty_choose_system)
+                .with_system(show_level_high_scores)
+                .with_system(seed_input_system)
+                .with_system(watch_replay)
+                .with_system(open_editor)
+                .with_system(open_achievements),
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Initialized).with_system(border_set_choose_system),
+            SystemSet::on_enter(GameState::Initialized)
+                .with_system(spawn_difficulty_buttons)
+                .with_system(spawn_high_score_panel),
         )
         .add_system_set(SystemSet::on_exit(GameState::Initialized).with_system(delete_buttons))
         .add_system_set(SystemSet::on_enter(GameState::Ready).with_system(init_game_components))
@@ -55,23 +69,80 @@ fn main() {
         )
         .add_system_set(
             SystemSet::on_update(GameState::Running)
-                .with_system(check_collisions)
-                .with_system(handle_snake_direction_input)
-                .with_system(enter_pause.before(handle_snake_direction_input))
-                .with_system(extra_bonus_timeout.before(handle_snake_direction_input))
-                .with_system(move_snake.before(handle_snake_direction_input))
-                .with_system(move_queue.before(move_snake))
-                .with_system(
-                    collision_handler
-                        .before(handle_snake_direction_input)
-                        .after(check_collisions),
-                ),
+                .with_system(enter_pause)
+                .with_system(step_world.after(enter_pause))
+                .with_system(keep_replay.after(step_world))
+                .with_system(keep_high_score.after(step_world))
+                .with_system(keep_lifetime_stats.after(step_world))
+                .with_system(unlock_achievements.after(keep_lifetime_stats))
+                .with_system(update_score.after(step_world))
+                .with_system(render_snake.after(step_world))
+                .with_system(render_bonuses.after(step_world))
+                .with_system(render_obstacles.after(step_world))
+                .with_system(render_effects.after(step_world)),
         )
         .add_system_set(
-            SystemSet::on_enter(GameState::Paused).with_system(compute_borders_visibility),
+            SystemSet::on_enter(GameState::Paused)
+                .with_system(compute_borders_visibility)
+                .with_system(record_pause),
         )
         .add_system_set(SystemSet::on_update(GameState::Paused).with_system(resume_game))
-        .add_system_set(SystemSet::on_update(GameState::Over).with_system(restart_game))
+        .add_system_set(
+            SystemSet::on_enter(GameState::Over)
+                .with_system(despawn_game_components)
+                .with_system(clear_typed_characters)
+                .with_system(spawn_game_summary),
+        )
+        .add_system_set(
+            SystemSet::on_update(GameState::Over)
+                .with_system(restart_game)
+                .with_system(watch_replay)
+                .with_system(enter_record_name.after(restart_game).after(watch_replay))
+                .with_system(update_game_summary.after(enter_record_name)),
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Over).with_system(despawn_game_summary))
+        .add_system_set(
+            SystemSet::on_enter(GameState::Won)
+                .with_system(despawn_game_components)
+                .with_system(clear_typed_characters)
+                .with_system(spawn_game_summary),
+        )
+        .add_system_set(
+            SystemSet::on_update(GameState::Won)
+                .with_system(restart_game)
+                .with_system(watch_replay)
+                .with_system(enter_record_name.after(restart_game).after(watch_replay))
+                .with_system(update_game_summary.after(enter_record_name)),
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Won).with_system(despawn_game_summary))
+        .add_system_set(SystemSet::on_enter(GameState::Editor).with_system(start_editor))
+        .add_system_set(
+            SystemSet::on_update(GameState::Editor)
+                .with_system(edit_level)
+                .with_system(editor_commands)
+                .with_system(render_editor.after(edit_level).after(editor_commands)),
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Editor).with_system(close_editor))
+        .add_system_set(
+            SystemSet::on_enter(GameState::Achievements).with_system(spawn_achievement_gallery),
+        )
+        .add_system_set(
+            SystemSet::on_update(GameState::Achievements).with_system(close_achievements),
+        )
+        .add_system_set(
+            SystemSet::on_exit(GameState::Achievements).with_system(despawn_achievement_gallery),
+        )
+        .add_system_set(SystemSet::on_enter(GameState::Replay).with_system(start_replay))
+        .add_system_set(
+            SystemSet::on_update(GameState::Replay)
+                .with_system(stop_replay)
+                .with_system(step_replay.after(stop_replay))
+                .with_system(update_score.after(step_replay))
+                .with_system(render_snake.after(step_replay))
+                .with_system(render_bonuses.after(step_replay))
+                .with_system(render_obstacles.after(step_replay))
+                .with_system(render_effects.after(step_replay)),
+        )
         .run();
 }
 
@@ -105,110 +176,19 @@ fn update_text(
     game_state: Res<State<GameState>>,
     app_font: Res<AppFont>,
     score: Res<Score>,
+    settings: Res<GameSettings>,
+    world: Option<Res<GameWorld>>,
     mut query: Query<(&mut Text, &mut Style), With<UserText>>,
     mut exit: EventWriter<AppExit>,
 ) {
     let (mut text, mut style) = query.single_mut();
     if let Some(font) = &**app_font {
-        *text = game_state.current().get_score_text(*score, font.clone());
+        *text = game_state
+            .current()
+            .get_score_text(*score, &settings, world.as_deref(), font.clone());
         *style = game_state.current().get_score_style();
     } else {
         eprintln!("Assets were not correctly loaded on startup");
         exit.send(AppExit);
     }
 }
-
-/// Every collision event handling.
-fn collision_handler(
-    mut commands: Commands,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut collision_event_reader: EventReader<CollisionEvent>,
-    mut bonus: Query<(&mut Transform, Entity, &Bonus)>,
-    mut score: ResMut<Score>,
-    mut game_state: ResMut<State<GameState>>,
-    mut border_query: Query<Entity, With<Border>>,
-    mut extra_bonus_timer: ResMut<ExtraBonusTimer>,
-    border_set: Res<Option<BorderSet>>,
-    snake: Query<Entity, With<Snake>>,
-    queue: Query<Entity, With<Queue>>,
-) {
-    if let Some(event) = collision_event_reader.iter().next() {
-        match event {
-            // If a bonus is collided, we increase the length of the queue, and
-            // others subsequent actions.
-            CollisionEvent::Bonus(points) => {
-                for _ in 0..*points {
-                    let queue = match **score {
-                        0 => Queue::First,
-                        _ => Queue::Other
-                    };
-                    // The queue is spawned out of the screen, and then moved
-                    // by the systems.
-                    queue.spawn(
-                        Transform::default().with_translation(Vec3::new(
-                            SCREEN_WIDTH,
-                            SCREEN_HEIGHT,
-                            0f32,
-                        )),
-                        &mut commands,
-                        &mut materials,
-                        &mut meshes,
-                    );
-                }
-                **score += points;
-                let mut extra_bonus_exists: bool = false;
-                // For each bonus, given the number of points, we proceed to ...
-                for (mut bonus_position, bonus_entity, bonus) in bonus.iter_mut() {
-                    match bonus {
-                        // Change its position if it a normal one that has been touched.
-                        Bonus::Normal if points == &1u32 => {
-                            bonus_position.translation =
-                                border_set.unwrap().compute_random_bonus_position();
-                        }
-                        // Despawn it if it is an extra bonus.
-                        Bonus::ExtraBonus if points == &5u32 => {
-                            extra_bonus_timer.reset();
-                            commands.entity(bonus_entity).despawn();
-                        }
-                        // If the normal bonus is touched while the extra bonus is touched, we don't
-                        // do anything.
-                        Bonus::ExtraBonus if points == &1u32 => {
-                            extra_bonus_exists = true;
-                        }
-                        _ => (),
-                    }
-                }
-                let mut rng = rand::thread_rng();
-                // If no extra bonus has been touched and none are on screen atm, we roll the dice
-                if points == &1 && !extra_bonus_exists && rng.gen_bool(CHANCE_OF_EXTRA_BONUS) {
-                    let extra_bonus_position = border_set.unwrap().compute_random_bonus_position();
-                    Bonus::ExtraBonus.spawn(
-                        Transform::default()
-                            .with_scale(Vec3::splat(BONUS_DIAMETER))
-                            .with_translation(extra_bonus_position),
-                        &mut commands,
-                        &mut materials,
-                        &mut meshes,
-                    );
-                }
-            }
-            // If a border is collided, we despawn all the game components
-            CollisionEvent::Border => {
-                let snake_entity = snake.single();
-                commands.entity(snake_entity).despawn();
-                for (_, bonus_entity, _) in bonus.iter() {
-                    commands.entity(bonus_entity).despawn();
-                }
-                for queue_entity in queue.iter() {
-                    commands.entity(queue_entity).despawn();
-                }
-                for border_entity in border_query.iter_mut() {
-                    commands.entity(border_entity).despawn();
-                }
-                extra_bonus_timer.reset();
-                game_state.set(GameState::Over).unwrap();
-            }
-        }
-    }
-}
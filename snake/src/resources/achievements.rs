@@ -0,0 +1,154 @@
+use super::lifetime_stats::LifetimeStats;
+use super::storage::{self, StorageError};
+use crate::common::*;
+use crate::components::prelude::Bonus;
+use crate::world::prelude::GameWorld;
+use serde::{Deserialize, Serialize};
+
+/// What makes the game check whether an achievement is unlocked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AchievementTrigger {
+    /// The snake collided with something.
+    Collision,
+    /// The game went on for a while.
+    Time,
+    /// The game ended, the snake died or the player won.
+    GameEnd,
+}
+
+/// A goal the player can reach, unlocked for good once reached.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Eat a first bonus.
+    FirstBite,
+    /// Eat 3 extra bonuses in one game.
+    Greedy,
+    /// Build a combo of 5 bonuses.
+    ComboMaster,
+    /// Reach a length of 100 on the Cross level.
+    CrossWalker,
+    /// Survive 5 minutes in one game.
+    Survivor,
+    /// Fill the whole board.
+    PerfectGame,
+    /// Play 50 games.
+    Veteran,
+}
+
+impl Achievement {
+    /// Every achievement, in the order they are shown in the gallery.
+    pub const ALL: [Self; 7] = [
+        Self::FirstBite,
+        Self::Greedy,
+        Self::ComboMaster,
+        Self::CrossWalker,
+        Self::Survivor,
+        Self::PerfectGame,
+        Self::Veteran,
+    ];
+
+    /// Returns the name of the achievement.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::FirstBite => "First bite",
+            Self::Greedy => "Greedy",
+            Self::ComboMaster => "Combo master",
+            Self::CrossWalker => "Cross walker",
+            Self::Survivor => "Survivor",
+            Self::PerfectGame => "Perfect game",
+            Self::Veteran => "Veteran",
+        }
+    }
+
+    /// Returns what the player has to do to unlock the achievement.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::FirstBite => "Eat a bonus.",
+            Self::Greedy => "Eat 3 extra bonuses in one game.",
+            Self::ComboMaster => "Build a combo of 5 bonuses.",
+            Self::CrossWalker => "Reach a length of 100 on Cross.",
+            Self::Survivor => "Survive 5 minutes in one game.",
+            Self::PerfectGame => "Fill the whole board.",
+            Self::Veteran => "Play 50 games.",
+        }
+    }
+
+    /// Returns what makes the game check whether the achievement is unlocked.
+    pub fn trigger(&self) -> AchievementTrigger {
+        match self {
+            Self::FirstBite | Self::Greedy | Self::ComboMaster | Self::CrossWalker => {
+                AchievementTrigger::Collision
+            }
+            Self::Survivor => AchievementTrigger::Time,
+            Self::PerfectGame | Self::Veteran => AchievementTrigger::GameEnd,
+        }
+    }
+
+    /// Returns whether the goal of the achievement is reached by the game,
+    /// or by every game played.
+    pub fn is_reached(&self, world: &GameWorld, lifetime_stats: &LifetimeStats) -> bool {
+        let stats = &world.stats;
+        match self {
+            Self::FirstBite => 0 < stats.bonuses_eaten.total(),
+            Self::Greedy => {
+                let eaten = stats.bonuses_eaten.total() - stats.bonuses_eaten.get(Bonus::Normal);
+                3 <= eaten
+            }
+            Self::ComboMaster => 5 <= world.score_breakdown.best_streak,
+            Self::CrossWalker => world.level.name == "Cross" && 100 <= world.snake.queue.len(),
+            Self::Survivor => 5f32 * 60f32 <= stats.duration,
+            Self::PerfectGame => world.over && stats.death_cause.is_none(),
+            Self::Veteran => 50 <= lifetime_stats.games,
+        }
+    }
+}
+
+/// The event sent when an achievement is unlocked.
+#[derive(Debug, Copy, Clone)]
+pub struct AchievementUnlocked(pub Achievement);
+
+/// The achievements unlocked by the player, kept across sessions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Achievements {
+    unlocked: Vec<Achievement>,
+}
+
+impl Achievements {
+    /// Returns whether the achievement is unlocked.
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// Returns the number of achievements unlocked.
+    pub fn count(&self) -> usize {
+        self.unlocked.len()
+    }
+
+    /// Unlocks the achievements with the given trigger whose goal is reached,
+    /// and returns the ones that weren't unlocked yet.
+    pub fn unlock(
+        &mut self,
+        trigger: AchievementTrigger,
+        world: &GameWorld,
+        lifetime_stats: &LifetimeStats,
+    ) -> Vec<Achievement> {
+        let unlocked: Vec<Achievement> = Achievement::ALL
+            .into_iter()
+            .filter(|achievement| achievement.trigger() == trigger)
+            .filter(|achievement| !self.is_unlocked(*achievement))
+            .filter(|achievement| achievement.is_reached(world, lifetime_stats))
+            .collect();
+        self.unlocked.extend(&unlocked);
+        unlocked
+    }
+
+    /// Loads the achievements unlocked in the previous sessions.
+    pub fn load() -> Result<Self, StorageError> {
+        storage::load(ACHIEVEMENTS_FILE_NAME)
+    }
+
+    /// Saves the achievements for the next sessions.
+    pub fn save(&self) -> Result<(), StorageError> {
+        storage::save(ACHIEVEMENTS_FILE_NAME, self)
+    }
+}
@@ -0,0 +1,275 @@
+use super::game_settings::GameSettings;
+use super::speed_progression::{SpeedCurve, SpeedProgression};
+use crate::common::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What happens when the snake hits a wall, an obstacle or the solid screen edges.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum WallRule {
+    /// The game is over.
+    #[default]
+    Deadly,
+    /// The snake turns around, its tail becoming its head.
+    Bounce,
+}
+
+impl fmt::Display for WallRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl WallRule {
+    /// Returns the other wall rule.
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Deadly => Self::Bounce,
+            Self::Bounce => Self::Deadly,
+        }
+    }
+}
+
+/// A preset of the settings making the game easier or harder.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// A slow snake that bounces on the walls, for the youngest players.
+    Easy,
+    /// The classic game.
+    #[default]
+    Normal,
+    /// A faster snake, starting longer, with fewer extra bonuses.
+    Hard,
+    /// A snake speeding up quickly until it reaches its highest speed.
+    Insane,
+    /// The settings picked one by one by the player.
+    Custom,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Difficulty {
+    /// Every difficulty, from the easiest to the hardest, then the custom one.
+    pub const ALL: [Self; 5] = [
+        Self::Easy,
+        Self::Normal,
+        Self::Hard,
+        Self::Insane,
+        Self::Custom,
+    ];
+
+    /// The difficulties bundling fixed settings, from the easiest to the hardest.
+    pub const PRESETS: [Self; 4] = [Self::Easy, Self::Normal, Self::Hard, Self::Insane];
+
+    /// Returns the factor the score is multiplied by, the harder the higher.
+    ///
+    /// The custom settings are scored like the hardest preset they are at
+    /// least as hard as in every way, like the easy one if there is none.
+    pub fn score_multiplier(&self, settings: &GameSettings) -> f32 {
+        match self {
+            Self::Easy => 0.5,
+            Self::Normal => 1f32,
+            Self::Hard => 1.5,
+            Self::Insane => 2f32,
+            Self::Custom => Self::PRESETS
+                .into_iter()
+                .filter(|preset| {
+                    preset
+                        .settings()
+                        .is_some_and(|preset_settings| is_as_hard(settings, &preset_settings))
+                })
+                .map(|preset| preset.score_multiplier(settings))
+                .fold(Self::Easy.score_multiplier(settings), f32::max),
+        }
+    }
+
+    /// Applies the preset to the given settings, the custom difficulty keeps
+    /// them as they are.
+    pub fn apply(&self, settings: &mut GameSettings) {
+        settings.difficulty = *self;
+        let Some(preset) = self.settings() else {
+            return;
+        };
+        settings.speed = preset.speed;
+        settings.extra_bonus_chance = preset.extra_bonus_chance;
+        settings.extra_bonus_lifetime = preset.extra_bonus_lifetime;
+        settings.max_extra_bonuses = preset.max_extra_bonuses;
+        settings.starting_length = preset.starting_length;
+        settings.wall_rule = preset.wall_rule;
+    }
+
+    /// Returns the settings bundled by the preset, none for the custom difficulty.
+    fn settings(&self) -> Option<GameSettings> {
+        let preset = match self {
+            Self::Easy => GameSettings {
+                speed: SpeedProgression {
+                    base: 200f32,
+                    curve: SpeedCurve::Constant,
+                    ..Default::default()
+                },
+                extra_bonus_chance: 0.25f64,
+                extra_bonus_lifetime: 15f32,
+                max_extra_bonuses: 3,
+                wall_rule: WallRule::Bounce,
+                ..Default::default()
+            },
+            Self::Normal => GameSettings::default(),
+            Self::Hard => GameSettings {
+                speed: SpeedProgression {
+                    base: 330f32,
+                    curve: SpeedCurve::Linear { increase: 3f32 },
+                    ..Default::default()
+                },
+                extra_bonus_chance: 0.07f64,
+                extra_bonus_lifetime: 7f32,
+                max_extra_bonuses: 1,
+                starting_length: 5,
+                ..Default::default()
+            },
+            Self::Insane => GameSettings {
+                speed: SpeedProgression {
+                    base: 400f32,
+                    curve: SpeedCurve::Exponential {
+                        rate: 0.02,
+                        max: SNAKE_MAX_SPEED_FACTOR,
+                    },
+                    ..Default::default()
+                },
+                extra_bonus_chance: 0.05f64,
+                extra_bonus_lifetime: 5f32,
+                max_extra_bonuses: 1,
+                starting_length: 10,
+                ..Default::default()
+            },
+            Self::Custom => return None,
+        };
+        Some(preset)
+    }
+}
+
+/// Returns whether the given settings are at least as hard as the given
+/// preset ones in every way.
+///
+/// The speeds are compared once [`RATED_PROGRESS`] bonuses are eaten and as
+/// many seconds played, so the speed curve counts along with the base speed.
+fn is_as_hard(settings: &GameSettings, preset: &GameSettings) -> bool {
+    let rated_speed = |settings: &GameSettings| {
+        settings
+            .speed
+            .speed_at(RATED_PROGRESS, RATED_PROGRESS as f32)
+    };
+    rated_speed(settings) >= rated_speed(preset)
+        && settings.extra_bonus_chance <= preset.extra_bonus_chance
+        && settings.max_extra_bonuses <= preset.max_extra_bonuses
+        && settings.extra_bonus_lifetime <= preset.extra_bonus_lifetime
+        && settings.starting_length >= preset.starting_length
+        && (settings.wall_rule == WallRule::Deadly || preset.wall_rule == WallRule::Bounce)
+}
+
+/// A setting of the custom difficulty, picked among the values of the presets.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum CustomSetting {
+    /// The speed the snake starts with.
+    Speed,
+    /// How often extra bonuses spawn, and how many can be on the playfield.
+    ExtraBonuses,
+    /// The time the player has to eat an extra bonus.
+    ExtraBonusLifetime,
+    /// The number of queue members the snake starts with.
+    StartingLength,
+}
+
+impl CustomSetting {
+    /// Changes the setting to its next value among the presets ones, from
+    /// the easiest to the hardest then back to the easiest. The difficulty
+    /// becomes the custom one.
+    pub fn cycle(&self, settings: &mut GameSettings) {
+        let presets: Vec<GameSettings> = Difficulty::PRESETS
+            .iter()
+            .filter_map(Difficulty::settings)
+            .collect();
+        let start = presets
+            .iter()
+            .rposition(|preset| self.matches(settings, preset))
+            .map_or(0, |position| position + 1);
+        let next = presets
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(presets.len())
+            .find(|preset| !self.matches(settings, preset));
+        if let Some(next) = next {
+            self.copy(next, settings);
+        }
+        settings.difficulty = Difficulty::Custom;
+    }
+
+    /// Returns whether the setting has the same value in both settings.
+    fn matches(&self, settings: &GameSettings, other: &GameSettings) -> bool {
+        match self {
+            Self::Speed => settings.speed.base == other.speed.base,
+            Self::ExtraBonuses => {
+                settings.extra_bonus_chance == other.extra_bonus_chance
+                    && settings.max_extra_bonuses == other.max_extra_bonuses
+            }
+            Self::ExtraBonusLifetime => settings.extra_bonus_lifetime == other.extra_bonus_lifetime,
+            Self::StartingLength => settings.starting_length == other.starting_length,
+        }
+    }
+
+    /// Copies the setting from the `from` settings to the `to` ones.
+    fn copy(&self, from: &GameSettings, to: &mut GameSettings) {
+        match self {
+            Self::Speed => to.speed.base = from.speed.base,
+            Self::ExtraBonuses => {
+                to.extra_bonus_chance = from.extra_bonus_chance;
+                to.max_extra_bonuses = from.max_extra_bonuses;
+            }
+            Self::ExtraBonusLifetime => to.extra_bonus_lifetime = from.extra_bonus_lifetime,
+            Self::StartingLength => to.starting_length = from.starting_length,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_settings_matching_a_preset_score_like_it() {
+        for preset in Difficulty::PRESETS {
+            let mut settings = GameSettings::default();
+            preset.apply(&mut settings);
+            let multiplier = preset.score_multiplier(&settings);
+            assert_eq!(Difficulty::Custom.score_multiplier(&settings), multiplier);
+        }
+    }
+
+    #[test]
+    fn custom_settings_score_like_their_easiest_setting() {
+        let mut settings = GameSettings::default();
+        Difficulty::Insane.apply(&mut settings);
+        CustomSetting::StartingLength.cycle(&mut settings);
+        assert_eq!(settings.difficulty, Difficulty::Custom);
+        assert_eq!(settings.starting_length, 0);
+        assert_eq!(settings.difficulty.score_multiplier(&settings), 1f32);
+        settings.wall_rule = WallRule::Bounce;
+        assert_eq!(settings.difficulty.score_multiplier(&settings), 0.5);
+    }
+
+    #[test]
+    fn custom_setting_cycles_through_the_preset_values() {
+        let mut settings = GameSettings::default();
+        let speeds: Vec<f32> = (0..4)
+            .map(|_| {
+                CustomSetting::Speed.cycle(&mut settings);
+                settings.speed.base
+            })
+            .collect();
+        assert_eq!(speeds, [330f32, 400f32, 200f32, SNAKE_SPEED_FACTOR]);
+    }
+}
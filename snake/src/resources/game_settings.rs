@@ -0,0 +1,128 @@
+use super::difficulty::{Difficulty, WallRule};
+use super::speed_progression::SpeedProgression;
+use crate::common::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The way the snake moves on the playfield.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum MovementMode {
+    /// The snake moves continuously, pixel by pixel.
+    #[default]
+    Continuous,
+    /// The playfield is a grid of cells of the snake size, and the snake
+    /// moves from one cell to another.
+    Grid,
+}
+
+impl fmt::Display for MovementMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl MovementMode {
+    /// Returns the other movement mode.
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Continuous => Self::Grid,
+            Self::Grid => Self::Continuous,
+        }
+    }
+}
+
+/// Whether a single snake is played, or two snakes share the keyboard.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum PlayMode {
+    /// A single player tries to fill the playfield.
+    #[default]
+    Solo,
+    /// Two players play against each other, the last snake alive wins.
+    Versus,
+}
+
+impl fmt::Display for PlayMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl PlayMode {
+    /// Returns the other play mode.
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Solo => Self::Versus,
+            Self::Versus => Self::Solo,
+        }
+    }
+}
+
+/// The settings picked by the user before starting a game.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    /// How the snake moves.
+    pub movement_mode: MovementMode,
+    /// The seed of the games, a random one is picked for each game if none is set.
+    ///
+    /// The random numbers are drawn with ChaCha8, so a seed can be shared
+    /// between platforms.
+    pub seed: Option<u64>,
+    /// The minimum distance between the snake head and a newly spawned bonus.
+    pub bonus_min_head_distance: f32,
+    /// How fast the snake moves, and how it speeds up along the game.
+    pub speed: SpeedProgression,
+    /// The difficulty preset the settings come from.
+    pub difficulty: Difficulty,
+    /// The chance that an extra bonus spawns each time the normal one is eaten.
+    pub extra_bonus_chance: f64,
+    /// The time the player has to eat an extra bonus, in seconds.
+    pub extra_bonus_lifetime: f32,
+    /// The maximum number of extra bonuses on the playfield at the same time.
+    pub max_extra_bonuses: u32,
+    /// The number of queue members the snake starts with.
+    pub starting_length: u32,
+    /// What happens when the snake hits a wall.
+    pub wall_rule: WallRule,
+    /// How many snakes are played.
+    pub play_mode: PlayMode,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            movement_mode: MovementMode::default(),
+            seed: None,
+            bonus_min_head_distance: BONUS_MIN_HEAD_DISTANCE,
+            speed: SpeedProgression::default(),
+            difficulty: Difficulty::default(),
+            extra_bonus_chance: CHANCE_OF_EXTRA_BONUS,
+            extra_bonus_lifetime: TIME_FOR_BONUS,
+            max_extra_bonuses: MAX_EXTRA_BONUSES,
+            starting_length: 0,
+            wall_rule: WallRule::default(),
+            play_mode: PlayMode::default(),
+        }
+    }
+}
+
+impl GameSettings {
+    /// Appends a digit to the seed.
+    ///
+    /// The digit is ignored if the seed would overflow.
+    pub fn push_seed_digit(&mut self, digit: u64) {
+        let seed = self
+            .seed
+            .unwrap_or_default()
+            .checked_mul(10)
+            .and_then(|seed| seed.checked_add(digit));
+        if seed.is_some() {
+            self.seed = seed;
+        }
+    }
+
+    /// Removes the last digit of the seed, the seed is unset once it has no digit left.
+    pub fn pop_seed_digit(&mut self) {
+        self.seed = self.seed.map(|seed| seed / 10).filter(|seed| *seed != 0);
+    }
+}
@@ -1,6 +1,8 @@
+use super::game_settings::GameSettings;
 use super::score::Score;
 
 use crate::common::*;
+use crate::world::prelude::{GameEvent, GameWorld, Player};
 use bevy::prelude::*;
 
 /// The game state defines the current status of the application.
@@ -12,7 +14,7 @@ pub enum GameState {
     /// The initial state : the app is started and a user choice is awaited.
     #[default]
     Initialized,
-    /// The ready state : the border set is defined, user input waited.
+    /// The ready state : the level is defined, user input waited.
     Ready,
     /// The running state: the user is playing.
     Running,
@@ -20,8 +22,40 @@ pub enum GameState {
     /// is waited in order to resume.
     Paused,
     /// Game over ! User lost, his input is waited in order to
-    /// either restart with the same border set or another one.
+    /// either restart with the same level or another one.
     Over,
+    /// The replay state: the last game is being watched again.
+    Replay,
+    /// Perfect game ! The snake filled the whole board, the user input
+    /// is waited in order to either restart with the same level or another one.
+    Won,
+    /// The level editor state: the user is building a level.
+    Editor,
+    /// The achievements state: the user is looking at the achievements gallery.
+    Achievements,
+}
+
+/// Returns the score shown while playing, the one of each player in a versus game.
+fn score_text(score: &str, world: Option<&GameWorld>) -> String {
+    match world.and_then(|world| world.rival.as_ref().map(|rival| (world.score, rival.score))) {
+        Some((one, two)) => format!("{} : {}  {} : {}", Player::One, one, Player::Two, two),
+        None => format!("Score : {}", score),
+    }
+}
+
+/// Returns the snake speed shown while playing, as a multiple of its starting
+/// speed, along with the combo going on.
+fn speed_text(world: Option<&GameWorld>) -> String {
+    let multiplier = world
+        .map(|world| world.settings.speed.multiplier(world.speed()))
+        .unwrap_or(1f32);
+    let streak = world
+        .map(|world| world.score_breakdown.active_streak(world.tick))
+        .unwrap_or_default();
+    match streak {
+        0 | 1 => format!("Speed : x{:.2}", multiplier),
+        _ => format!("Speed : x{:.2}\nCombo : x{}", multiplier, streak),
+    }
 }
 
 impl GameState {
@@ -29,41 +63,86 @@ impl GameState {
     ///
     /// Useful when we need to show text, as borders can overflow the text.
     pub fn are_borders_visible(&self) -> bool {
-        matches!(self, Self::Running | Self::Ready)
+        matches!(
+            self,
+            Self::Running | Self::Ready | Self::Replay | Self::Editor
+        )
+    }
+
+    /// Returns the state the game ends on after the given event, if it ends the game.
+    pub fn from_ending_event(event: &GameEvent) -> Option<Self> {
+        match event {
+            GameEvent::GameOver => Some(Self::Over),
+            GameEvent::BoardFull => Some(Self::Won),
+            _ => None,
+        }
     }
 
     /// Returns the text that has to be displayed to the user.
-    fn get_score_text_value(&self, score: &str) -> String {
+    fn get_score_text_value(
+        &self,
+        score: &str,
+        settings: &GameSettings,
+        world: Option<&GameWorld>,
+    ) -> String {
         match self {
-            GameState::Running => format!("Score : {}", score),
-            GameState::Over => format!("Game over\nYour score : {}\nPress 'R' to restart.\nPress 'ESC' to choose another border set.", score),
+            GameState::Running => format!("{}\n{}", score_text(score, world), speed_text(world)),
+            GameState::Replay => format!(
+                "Replay - {}\n{}\nPress 'ESC' to stop.",
+                score_text(score, world),
+                speed_text(world)
+            ),
             GameState::Paused => "The game has been paused\nPress 'P' to resume.".into(),
-            GameState::Ready => String::default(),
-            GameState::Initialized => "Choose a border set".into(),
+            // The end of the game and the achievements have their own screens.
+            GameState::Ready | GameState::Over | GameState::Won | GameState::Achievements => {
+                String::default()
+            }
+            GameState::Editor => "Level editor\nDrag : draw or move a wall, 'Shift' + drag : resize it.\nRight click : delete a wall, 'Space' : set the spawn.\n'W' : change the edges, 'N' : new level.\n'T' : test, 'Enter' : save, 'ESC' : back.".into(),
+            GameState::Initialized => format!(
+                "Choose a level\nMovement : {}, press 'G' to change it.\nDifficulty : {} (score x{}), speed curve : {} ('C') by {} ('D'), walls : {} ('K').\nSpeed : {} ('F'), extra bonuses : {:.0}% up to {} ('B') for {}s ('L'), length : {} ('N').\nPlayers : {}, press 'T' to change it.\nSeed : {}, type digits to change it.\nPress 'V' to watch the last replay.\nPress 'E' to edit a level, 'A' to see the achievements.",
+                settings.movement_mode,
+                settings.difficulty,
+                settings.difficulty.score_multiplier(settings),
+                settings.speed.curve,
+                settings.speed.driver,
+                settings.wall_rule,
+                settings.speed.base,
+                settings.extra_bonus_chance * 100f64,
+                settings.max_extra_bonuses,
+                settings.extra_bonus_lifetime,
+                settings.starting_length,
+                settings.play_mode,
+                settings
+                    .seed
+                    .map(|seed| seed.to_string())
+                    .unwrap_or_else(|| "random".into())
+            ),
         }
     }
 
     /// Returns the style of the text displayed to the user.
     fn get_score_text_style(&self, font: Handle<Font>) -> TextStyle {
         match &self {
-            GameState::Running => TextStyle {
+            GameState::Running | GameState::Replay | GameState::Editor => TextStyle {
                 font_size: 16f32,
                 color: Color::WHITE,
                 font,
             },
-            GameState::Over | GameState::Paused | GameState::Initialized => TextStyle {
+            GameState::Paused | GameState::Initialized => TextStyle {
                 font_size: 30f32,
                 color: Color::WHITE,
                 font,
             },
-            GameState::Ready => TextStyle::default(),
+            GameState::Ready | GameState::Over | GameState::Won | GameState::Achievements => {
+                TextStyle::default()
+            }
         }
     }
 
     /// Returns the style of the score.
     pub fn get_score_style(&self) -> Style {
         match &self {
-            GameState::Running => Style {
+            GameState::Running | GameState::Replay => Style {
                 position_type: PositionType::Absolute,
                 position: UiRect {
                     bottom: Val::Px(10f32),
@@ -74,7 +153,7 @@ impl GameState {
                 overflow: Overflow::Hidden,
                 ..default()
             },
-            GameState::Paused | GameState::Over => Style {
+            GameState::Paused => Style {
                 margin: UiRect::all(Val::Auto),
                 align_items: AlignItems::Center,
                 align_self: AlignSelf::Center,
@@ -90,16 +169,36 @@ impl GameState {
                 },
                 ..default()
             },
-            GameState::Ready => Style {
-                display: Display::None,
+            GameState::Editor => Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(10f32),
+                    left: Val::Px(10f32),
+                    ..default()
+                },
                 ..default()
             },
+            GameState::Ready | GameState::Over | GameState::Won | GameState::Achievements => {
+                Style {
+                    display: Display::None,
+                    ..default()
+                }
+            }
         }
     }
 
     /// Returns the text of the score.
-    pub fn get_score_text(&self, score: Score, font: Handle<Font>) -> Text {
+    pub fn get_score_text(
+        &self,
+        score: Score,
+        settings: &GameSettings,
+        world: Option<&GameWorld>,
+        font: Handle<Font>,
+    ) -> Text {
         let text_style: TextStyle = self.get_score_text_style(font);
-        Text::from_section(self.get_score_text_value(&score.to_string()), text_style)
+        Text::from_section(
+            self.get_score_text_value(&score.to_string(), settings, world),
+            text_style,
+        )
     }
 }
@@ -0,0 +1,216 @@
+use super::difficulty::Difficulty;
+use super::storage::{self, StorageError};
+use crate::common::*;
+use serde::{Deserialize, Serialize};
+
+/// A score kept in a high-score table.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    /// The name typed by the player.
+    pub name: String,
+    /// The score of the game.
+    pub score: u32,
+}
+
+/// The best scores made on a level with a difficulty, from the highest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreTable {
+    /// The name of the level.
+    pub level: String,
+    /// The difficulty the level has been played with.
+    pub difficulty: Difficulty,
+    /// The scores, at most [`HIGH_SCORES_COUNT`] of them.
+    pub scores: Vec<HighScore>,
+}
+
+/// A score beating a record, waiting for the player to type their name.
+#[derive(Debug, Clone)]
+pub struct PendingRecord {
+    /// The name of the level.
+    pub level: String,
+    /// The difficulty the level has been played with.
+    pub difficulty: Difficulty,
+    /// The score of the game.
+    pub score: u32,
+    /// The name typed so far.
+    pub name: String,
+}
+
+/// The high-score tables of every level and difficulty played.
+///
+/// They are saved to a file, or to the local storage of the browser build.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    tables: Vec<HighScoreTable>,
+    /// The last name typed, suggested for the next record.
+    #[serde(default)]
+    last_name: String,
+    /// The record beaten by the last game, if its name is being typed.
+    #[serde(skip)]
+    pub pending: Option<PendingRecord>,
+}
+
+impl HighScores {
+    /// Returns the best scores made on the level with the difficulty, from the highest.
+    pub fn table(&self, level: &str, difficulty: Difficulty) -> &[HighScore] {
+        self.tables
+            .iter()
+            .find(|table| table.level == level && table.difficulty == difficulty)
+            .map(|table| table.scores.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the score enters the table of the level with the difficulty.
+    pub fn is_record(&self, level: &str, difficulty: Difficulty, score: u32) -> bool {
+        let scores = self.table(level, difficulty);
+        0 < score
+            && (scores.len() < HIGH_SCORES_COUNT
+                || scores.last().is_some_and(|lowest| lowest.score < score))
+    }
+
+    /// Waits for the name of the player before keeping the score, if it beats a record.
+    pub fn start_record(&mut self, level: &str, difficulty: Difficulty, score: u32) {
+        if self.is_record(level, difficulty, score) {
+            self.pending = Some(PendingRecord {
+                level: level.into(),
+                difficulty,
+                score,
+                name: self.last_name.clone(),
+            });
+        }
+    }
+
+    /// Keeps the pending record with the name typed, and returns whether there was one.
+    pub fn confirm_record(&mut self) -> bool {
+        let record = match self.pending.take() {
+            Some(record) => record,
+            None => return false,
+        };
+        let name = match record.name.trim() {
+            "" => DEFAULT_PLAYER_NAME.to_string(),
+            name => name.to_string(),
+        };
+        self.last_name = name.clone();
+        let table_index = self
+            .tables
+            .iter()
+            .position(|table| table.level == record.level && table.difficulty == record.difficulty)
+            .unwrap_or_else(|| {
+                self.tables.push(HighScoreTable {
+                    level: record.level.clone(),
+                    difficulty: record.difficulty,
+                    scores: Vec::new(),
+                });
+                self.tables.len() - 1
+            });
+        let scores = &mut self.tables[table_index].scores;
+        // Older scores stay above the new ones in case of a tie.
+        let rank = scores
+            .iter()
+            .position(|high_score| high_score.score < record.score)
+            .unwrap_or(scores.len());
+        scores.insert(
+            rank,
+            HighScore {
+                name,
+                score: record.score,
+            },
+        );
+        scores.truncate(HIGH_SCORES_COUNT);
+        true
+    }
+
+    /// Returns the text listing the best scores made on the level with the difficulty.
+    pub fn table_text(&self, level: &str, difficulty: Difficulty) -> String {
+        let scores = self.table(level, difficulty);
+        if scores.is_empty() {
+            return format!("No high score yet on {} ({}).", level, difficulty);
+        }
+        let lines: Vec<String> = scores
+            .iter()
+            .enumerate()
+            .map(|(i, high_score)| format!("{}. {}  {}", i + 1, high_score.name, high_score.score))
+            .collect();
+        format!(
+            "High scores on {} ({}) :\n{}",
+            level,
+            difficulty,
+            lines.join("\n")
+        )
+    }
+
+    /// Loads the high scores saved by the previous sessions.
+    pub fn load() -> Result<Self, StorageError> {
+        storage::load(HIGH_SCORES_FILE_NAME)
+    }
+
+    /// Saves the high scores for the next sessions.
+    pub fn save(&self) -> Result<(), StorageError> {
+        storage::save(HIGH_SCORES_FILE_NAME, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVEL: &str = "Screen";
+
+    /// Keeps the given score with the given name, and returns whether it was a record.
+    fn record(high_scores: &mut HighScores, name: &str, score: u32) -> bool {
+        high_scores.start_record(LEVEL, Difficulty::Normal, score);
+        if let Some(pending) = &mut high_scores.pending {
+            pending.name = name.to_string();
+        }
+        high_scores.confirm_record()
+    }
+
+    fn names(high_scores: &HighScores) -> Vec<&str> {
+        high_scores
+            .table(LEVEL, Difficulty::Normal)
+            .iter()
+            .map(|high_score| high_score.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn confirmed_record_is_ranked_in_its_table() {
+        let mut high_scores = HighScores::default();
+        assert!(record(&mut high_scores, "A", 10));
+        assert!(record(&mut high_scores, "B", 20));
+        // Older scores stay above the new ones in case of a tie.
+        assert!(record(&mut high_scores, "C", 10));
+        assert_eq!(names(&high_scores), vec!["B", "A", "C"]);
+        assert!(high_scores.pending.is_none());
+        assert!(high_scores.table(LEVEL, Difficulty::Hard).is_empty());
+    }
+
+    #[test]
+    fn table_keeps_the_best_scores_only() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=HIGH_SCORES_COUNT as u32 {
+            assert!(record(&mut high_scores, &score.to_string(), score * 10));
+        }
+        assert!(!record(&mut high_scores, "Low", 10));
+        assert!(record(&mut high_scores, "High", 15));
+        assert_eq!(names(&high_scores), vec!["5", "4", "3", "2", "High"]);
+    }
+
+    #[test]
+    fn confirm_record_without_record_does_nothing() {
+        let mut high_scores = HighScores::default();
+        assert!(!high_scores.confirm_record());
+        assert!(!record(&mut high_scores, "Zero", 0));
+        assert!(high_scores.table(LEVEL, Difficulty::Normal).is_empty());
+    }
+
+    #[test]
+    fn blank_name_is_replaced_and_last_name_is_suggested() {
+        let mut high_scores = HighScores::default();
+        assert!(record(&mut high_scores, "  ", 10));
+        assert_eq!(names(&high_scores), vec![DEFAULT_PLAYER_NAME]);
+        assert!(record(&mut high_scores, " Ann ", 20));
+        high_scores.start_record(LEVEL, Difficulty::Normal, 30);
+        assert_eq!(high_scores.pending.as_ref().unwrap().name, "Ann");
+    }
+}
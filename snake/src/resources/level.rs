@@ -0,0 +1,257 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use rand::seq::SliceRandom;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io};
+
+use crate::common::GENERATED_LEVELS_HIGH_SCORES_NAME;
+use crate::world::prelude::{Aabb, GameRng, Maze, SnakeDirection};
+
+/// A wall of a level, hitting it results in a game over.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Wall {
+    /// The center of the wall.
+    pub center: Vec2,
+    /// The width and the height of the wall.
+    pub size: Vec2,
+}
+
+/// How an obstacle moves over time, each motion being periodic.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum Motion {
+    /// Goes back and forth between its initial center and the center moved by `offset`.
+    Patrol {
+        /// The translation to the furthest point of the patrol.
+        offset: Vec2,
+        /// The duration of a round trip, in seconds.
+        period: f32,
+    },
+    /// Rotates around the pivot, counterclockwise, or clockwise if the period is negative.
+    Rotate {
+        /// The point the obstacle rotates around.
+        pivot: Vec2,
+        /// The duration of a whole turn, in seconds.
+        period: f32,
+    },
+    /// Grows and shrinks around its center.
+    Pulse {
+        /// The size factor of the obstacle when it is the biggest or the smallest.
+        scale: f32,
+        /// The duration of a whole pulse, in seconds.
+        period: f32,
+    },
+}
+
+/// An obstacle of a level, a wall that moves.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Obstacle {
+    /// The obstacle at the start of the game.
+    pub wall: Wall,
+    /// How the obstacle moves.
+    pub motion: Motion,
+}
+
+/// Two linked portals, the snake entering one of them comes out of the other.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PortalPair {
+    /// The center of the first portal.
+    pub a: Vec2,
+    /// The center of the second portal.
+    pub b: Vec2,
+}
+
+/// Where the snake starts in a level.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Spawn {
+    /// The initial position of the snake head.
+    pub position: Vec2,
+    /// The direction the snake is facing, it can't start by going the opposite way.
+    /// The snake can start toward any direction if none is set.
+    #[serde(default)]
+    pub direction: Option<SnakeDirection>,
+}
+
+/// The error that can happen while saving a level.
+#[derive(Debug)]
+pub enum LevelError {
+    /// The level file couldn't be read or written.
+    Io(io::Error),
+    /// The level couldn't be serialized or deserialized.
+    Format(ron::Error),
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Level file couldn't be accessed : {}", error),
+            Self::Format(error) => write!(f, "Level format is invalid : {}", error),
+        }
+    }
+}
+
+impl From<io::Error> for LevelError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::Error> for LevelError {
+    fn from(error: ron::Error) -> Self {
+        Self::Format(error)
+    }
+}
+
+/// What happens when the snake goes through the screen edges.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum EdgeRule {
+    /// The snake comes back from the opposite edge.
+    #[default]
+    Wrap,
+    /// The screen edges are deadly, as the walls.
+    Solid,
+    /// The snake comes back from the opposite edge, mirrored as on a Klein
+    /// bottle : going through the left or right edge flips it upside down.
+    Mirror,
+    /// The snake bounces on the screen edges : it turns around, its tail
+    /// becoming its head.
+    Bounce,
+}
+
+/// A level is the arena a game is played on, described in an asset file.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, TypeUuid)]
+#[uuid = "2d4b8f6c-5a1e-4c39-9f0d-7b3e6a81c254"]
+pub struct Level {
+    /// The name of the level, shown on the level picker.
+    pub name: String,
+    /// A short description of the level.
+    #[serde(default)]
+    pub description: String,
+    /// The rank of the level on the level picker, the lowest comes first.
+    #[serde(default)]
+    pub order: i32,
+    /// The walls of the level.
+    pub walls: Vec<Wall>,
+    /// The moving obstacles of the level.
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    /// The portals of the level.
+    #[serde(default)]
+    pub portals: Vec<PortalPair>,
+    /// Where the snake starts.
+    pub spawn: Spawn,
+    /// What happens when the snake goes through the screen edges.
+    #[serde(default)]
+    pub edges: EdgeRule,
+    /// The seed the level has been generated from, none for the levels of the files.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Level {
+    /// Returns the hitboxes of the walls.
+    pub fn get_borders(&self) -> Vec<Aabb> {
+        self.walls
+            .iter()
+            .map(|wall| Aabb::new(wall.center.extend(0f32), wall.size))
+            .collect()
+    }
+
+    /// Returns the initial position of the snake head.
+    pub fn get_snake_initial_position(&self) -> Vec3 {
+        self.spawn.position.extend(0f32)
+    }
+
+    /// Returns the name the high scores of the level are kept under.
+    ///
+    /// The generated mazes share a single table, so a new one isn't saved for
+    /// each random seed.
+    pub fn high_scores_name(&self) -> &str {
+        match self.seed {
+            Some(_) => GENERATED_LEVELS_HIGH_SCORES_NAME,
+            None => &self.name,
+        }
+    }
+
+    /// Returns the name of the maze level generated from the given seed.
+    pub fn generated_name(seed: u64) -> String {
+        format!("Generated {}", seed)
+    }
+
+    /// Generates a maze level from the given seed, or from a random one if none is given.
+    ///
+    /// The snake spawns in the middle of a room, facing one of its passages.
+    pub fn generate(seed: Option<u64>) -> Self {
+        let mut rng = GameRng::new(seed);
+        let maze = Maze::generate(&mut rng);
+        let (room, spawn_position) = maze.random_room(&mut rng);
+        Self {
+            name: Self::generated_name(rng.seed()),
+            description: "A maze generated from a seed.".into(),
+            order: i32::MAX,
+            walls: maze.walls(),
+            obstacles: Vec::new(),
+            portals: Vec::new(),
+            spawn: Spawn {
+                position: spawn_position,
+                direction: maze.passages(room).choose(&mut rng).copied(),
+            },
+            edges: EdgeRule::Solid,
+            seed: Some(rng.seed()),
+        }
+    }
+
+    /// Loads a level from the RON file at the given path.
+    pub fn load(path: &str) -> Result<Self, LevelError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Saves the level as a RON file at the given path.
+    pub fn save(&self, path: &str) -> Result<(), LevelError> {
+        fs::write(
+            path,
+            ron::ser::to_string_pretty(self, PrettyConfig::default())?,
+        )?;
+        Ok(())
+    }
+}
+
+/// The handles of the levels found in the assets, loaded on startup.
+#[derive(Deref, DerefMut, Debug, Clone, Default)]
+pub struct LevelHandles(pub Vec<Handle<Level>>);
+
+/// Loads the levels described in RON (`.level.ron`) or JSON (`.level.json`) files.
+#[derive(Debug, Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let level: Level = if load_context.path().extension() == Some("json".as_ref()) {
+                serde_json::from_slice(bytes)?
+            } else {
+                ron::de::from_bytes(bytes)?
+            };
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron", "level.json"]
+    }
+}
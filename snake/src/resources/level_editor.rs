@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+use crate::common::*;
+
+use super::level::{EdgeRule, Level, Spawn, Wall};
+
+/// What the user is doing with the mouse in the level editor.
+#[derive(Debug, Copy, Clone)]
+enum Drag {
+    /// A new wall is drawn from the given corner.
+    Draw { wall: usize, from: Vec2 },
+    /// A wall is moved, the cursor keeping the given offset from its center.
+    Move { wall: usize, offset: Vec2 },
+    /// A wall is resized, its corner opposite to the cursor staying still.
+    Resize { wall: usize, anchor: Vec2 },
+}
+
+/// The level being edited, along with the state of the editor.
+///
+/// It is kept when the editor is left, so the level can be test-played and
+/// edited again.
+#[derive(Debug)]
+pub struct LevelEditor {
+    /// The level being edited.
+    pub level: Level,
+    /// The handle of the level once it has been saved, so the level picker
+    /// shows the last saved version.
+    pub handle: Option<Handle<Level>>,
+    /// The name of the file the level is saved to, without its folder.
+    pub file_name: String,
+    /// Whether the level is being test-played, the game then comes back to the editor.
+    pub test_playing: bool,
+    /// The current mouse drag, if any.
+    drag: Option<Drag>,
+}
+
+impl LevelEditor {
+    /// Creates an editor for a new empty level, identified by the given number.
+    pub fn new(number: u32) -> Self {
+        Self {
+            level: Level {
+                name: format!("Custom {}", number),
+                description: String::default(),
+                order: i32::MAX,
+                walls: Vec::new(),
+                obstacles: Vec::new(),
+                portals: Vec::new(),
+                spawn: Spawn {
+                    position: Vec2::ZERO,
+                    direction: None,
+                },
+                edges: EdgeRule::default(),
+                seed: None,
+            },
+            handle: None,
+            file_name: format!("custom_{}.level.ron", number),
+            test_playing: false,
+            drag: None,
+        }
+    }
+
+    /// Returns the index of the topmost wall under the given position.
+    fn wall_at(&self, position: Vec2) -> Option<usize> {
+        self.level.walls.iter().rposition(|wall| {
+            let half_size = wall.size / 2f32;
+            (position - wall.center).abs().cmple(half_size).all()
+        })
+    }
+
+    /// Starts dragging at the given position : the wall under the cursor is
+    /// moved, or resized if `resize` is set. A new wall is drawn if there is none.
+    pub fn start_drag(&mut self, position: Vec2, resize: bool) {
+        let position = snap_to_grid_lines(position);
+        self.drag = Some(match self.wall_at(position) {
+            Some(wall) if resize => {
+                let Wall { center, size } = self.level.walls[wall];
+                // The anchor is the corner opposite to the grabbed quarter of the wall.
+                let anchor = center - (position - center).signum() * size / 2f32;
+                Drag::Resize { wall, anchor }
+            }
+            Some(wall) => Drag::Move {
+                wall,
+                offset: self.level.walls[wall].center - position,
+            },
+            None => {
+                self.level.walls.push(Wall {
+                    center: position,
+                    size: Vec2::ZERO,
+                });
+                Drag::Draw {
+                    wall: self.level.walls.len() - 1,
+                    from: position,
+                }
+            }
+        });
+    }
+
+    /// Updates the dragged wall with the cursor position.
+    pub fn drag_to(&mut self, position: Vec2) {
+        let position = snap_to_grid_lines(position);
+        match self.drag {
+            Some(Drag::Draw { wall, from: corner })
+            | Some(Drag::Resize {
+                wall,
+                anchor: corner,
+            }) => {
+                self.level.walls[wall] = Wall {
+                    center: (corner + position) / 2f32,
+                    size: (position - corner).abs(),
+                };
+            }
+            Some(Drag::Move { wall, offset }) => {
+                self.level.walls[wall].center = position + offset;
+            }
+            None => {}
+        }
+    }
+
+    /// Ends the current drag, the walls without any area are removed.
+    pub fn end_drag(&mut self) {
+        self.drag = None;
+        self.level
+            .walls
+            .retain(|wall| 0f32 < wall.size.x && 0f32 < wall.size.y);
+    }
+
+    /// Returns whether a wall is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Deletes the topmost wall under the given position.
+    pub fn delete_wall_at(&mut self, position: Vec2) {
+        if let Some(wall) = self.wall_at(position) {
+            self.level.walls.remove(wall);
+        }
+    }
+
+    /// Moves the snake spawn on the cell containing the given position.
+    pub fn set_spawn(&mut self, position: Vec2) {
+        let cell =
+            ((position - Vec2::new(MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT)) / SNAKE_SIZE).floor();
+        self.level.spawn.position =
+            Vec2::new(MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT) + (cell + 0.5) * SNAKE_SIZE;
+    }
+
+    /// Switches to the next screen edges behavior.
+    pub fn toggle_edges(&mut self) {
+        self.level.edges = match self.level.edges {
+            EdgeRule::Wrap => EdgeRule::Solid,
+            EdgeRule::Solid => EdgeRule::Mirror,
+            EdgeRule::Mirror => EdgeRule::Bounce,
+            EdgeRule::Bounce => EdgeRule::Wrap,
+        };
+    }
+}
+
+/// Returns the closest intersection of the cells edges, so the drawn walls
+/// match the grid movement mode.
+fn snap_to_grid_lines(position: Vec2) -> Vec2 {
+    let origin = Vec2::new(MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT);
+    origin + ((position - origin) / SNAKE_SIZE).round() * SNAKE_SIZE
+}
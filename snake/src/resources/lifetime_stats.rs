@@ -0,0 +1,66 @@
+use super::storage::{self, StorageError};
+use crate::common::*;
+use crate::world::prelude::{BonusCounts, DeathCause, GameStats};
+use serde::{Deserialize, Serialize};
+
+/// The statistics of every game played, kept across sessions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    /// The number of games played.
+    pub games: u32,
+    /// The number of games won.
+    pub wins: u32,
+    /// The time the snake moved for, in seconds.
+    pub duration: f32,
+    /// The distance travelled by the head, in pixels.
+    pub distance: f32,
+    /// The bonuses eaten, by kind.
+    pub bonuses_eaten: BonusCounts,
+    /// The extra bonuses that expired before the snake ate them, by kind.
+    pub bonuses_missed: BonusCounts,
+    /// The turns taken by the snake.
+    pub turns: u32,
+    /// The longest the queue has been.
+    pub max_length: usize,
+    /// The number of times the snake died of each cause.
+    pub deaths: Vec<(DeathCause, u32)>,
+}
+
+impl LifetimeStats {
+    /// Adds the statistics of a game that ended.
+    pub fn add(&mut self, game: &GameStats) {
+        self.games += 1;
+        self.duration += game.duration;
+        self.distance += game.distance;
+        for (bonus, count) in game.bonuses_eaten.iter() {
+            self.bonuses_eaten.add(*bonus, *count);
+        }
+        for (bonus, count) in game.bonuses_missed.iter() {
+            self.bonuses_missed.add(*bonus, *count);
+        }
+        self.turns += game.turns;
+        self.max_length = self.max_length.max(game.max_length);
+        match game.death_cause {
+            Some(cause) => match self.deaths.iter_mut().find(|(other, _)| *other == cause) {
+                Some((_, count)) => *count += 1,
+                None => self.deaths.push((cause, 1)),
+            },
+            None => self.wins += 1,
+        }
+    }
+
+    /// Returns what killed the snake the most, with the number of times it did.
+    pub fn main_death_cause(&self) -> Option<(DeathCause, u32)> {
+        self.deaths.iter().copied().max_by_key(|(_, count)| *count)
+    }
+
+    /// Loads the statistics saved by the previous sessions.
+    pub fn load() -> Result<Self, StorageError> {
+        storage::load(LIFETIME_STATS_FILE_NAME)
+    }
+
+    /// Saves the statistics for the next sessions.
+    pub fn save(&self) -> Result<(), StorageError> {
+        storage::save(LIFETIME_STATS_FILE_NAME, self)
+    }
+}
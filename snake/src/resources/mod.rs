@@ -1,22 +1,40 @@
-pub mod border_set;
 pub mod font {
     use bevy::prelude::{Deref, DerefMut, Font, Handle};
     /// The app font, loaded on startup and returned as a ressource.
     #[derive(Deref, DerefMut, Debug, Clone, Default)]
     pub struct AppFont(Option<Handle<Font>>);
 }
+pub mod achievements;
+pub mod difficulty;
+pub mod game_settings;
 pub mod game_state;
+pub mod high_scores;
+pub mod level;
+pub mod level_editor;
+pub mod lifetime_stats;
+pub mod simulation_clock;
+pub mod speed_progression;
+pub mod storage;
 pub mod score {
     use bevy::prelude::{Deref, DerefMut};
-    /// The score equals the snake length, and defines the
-    /// user progress in the game.
+    /// The score of the game shown to the user, rewarded by the eaten bonuses,
+    /// the combos and the quickness, whatever the snake length.
     #[derive(Default, Deref, DerefMut, Debug, Copy, Clone)]
     pub struct Score(pub u32);
 }
 
 pub mod prelude {
-    pub use super::border_set::*;
+    pub use super::achievements::*;
+    pub use super::difficulty::*;
     pub use super::font::AppFont;
+    pub use super::game_settings::*;
     pub use super::game_state::GameState;
+    pub use super::high_scores::*;
+    pub use super::level::*;
+    pub use super::level_editor::LevelEditor;
+    pub use super::lifetime_stats::LifetimeStats;
     pub use super::score::Score;
+    pub use super::simulation_clock::SimulationClock;
+    pub use super::speed_progression::*;
+    pub use super::storage::StorageError;
 }
@@ -0,0 +1,33 @@
+use crate::common::*;
+
+/// The simulation clock accumulates the frame time, in order to advance the
+/// game world by fixed ticks whatever the frame rate is.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SimulationClock {
+    /// The frame time not simulated yet, in seconds.
+    accumulator: f32,
+}
+
+impl SimulationClock {
+    /// Adds the frame time to the clock, and returns the number of ticks
+    /// that have to be simulated.
+    pub fn advance(&mut self, delta_seconds: f32) -> u32 {
+        self.accumulator += delta_seconds;
+        let mut ticks = 0;
+        while SIMULATION_TIME_STEP <= self.accumulator {
+            self.accumulator -= SIMULATION_TIME_STEP;
+            ticks += 1;
+        }
+        if MAX_TICKS_PER_FRAME < ticks {
+            ticks = MAX_TICKS_PER_FRAME;
+        }
+        ticks
+    }
+
+    /// Returns the progress toward the next tick, between 0 and 1.
+    ///
+    /// The renderer uses it to interpolate the positions between two ticks.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / SIMULATION_TIME_STEP
+    }
+}
@@ -0,0 +1,146 @@
+use crate::common::{SNAKE_MAX_SPEED_FACTOR, SNAKE_SPEED_FACTOR};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What makes the snake speed progress during a game.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum SpeedDriver {
+    /// The speed raises with the bonuses eaten, each bonus being a unit of progress.
+    #[default]
+    Bonuses,
+    /// The speed raises with the time played, each second being a unit of progress.
+    Time,
+}
+
+impl fmt::Display for SpeedDriver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl SpeedDriver {
+    /// Returns the other speed driver.
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Bonuses => Self::Time,
+            Self::Time => Self::Bonuses,
+        }
+    }
+}
+
+/// The shape of the snake speed raise, given the progress made.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum SpeedCurve {
+    /// The speed never changes.
+    Constant,
+    /// The speed raises by `increase` each time `every` units of progress are made.
+    Step { every: f32, increase: f32 },
+    /// The speed raises by `increase` for each unit of progress.
+    Linear { increase: f32 },
+    /// The speed is multiplied by `1 + rate` for each unit of progress, up to `max`.
+    Exponential { rate: f32, max: f32 },
+}
+
+impl fmt::Display for SpeedCurve {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Constant => "Constant",
+            Self::Step { .. } => "Step",
+            Self::Linear { .. } => "Linear",
+            Self::Exponential { .. } => "Exponential",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl SpeedCurve {
+    /// Returns the next curve, with its default parameters.
+    pub fn toggled(&self) -> Self {
+        match self {
+            Self::Constant => Self::Step {
+                every: 5f32,
+                increase: 20f32,
+            },
+            Self::Step { .. } => Self::Linear { increase: 3f32 },
+            Self::Linear { .. } => Self::Exponential {
+                rate: 0.01,
+                max: 2f32 * SNAKE_SPEED_FACTOR,
+            },
+            Self::Exponential { .. } => Self::Constant,
+        }
+    }
+
+    /// Returns the speed reached from the given base speed once the given progress is made.
+    fn apply(&self, base: f32, progress: f32) -> f32 {
+        match *self {
+            Self::Constant => base,
+            Self::Step { every, increase } => base + (progress / every).floor() * increase,
+            Self::Linear { increase } => base + progress * increase,
+            Self::Exponential { rate, max } => (base * (1f32 + rate).powf(progress)).min(max),
+        }
+    }
+}
+
+/// How fast the snake moves, and how it speeds up along the game.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeedProgression {
+    /// The speed the snake starts with, in pixels per second.
+    pub base: f32,
+    /// The shape of the speed raise.
+    pub curve: SpeedCurve,
+    /// What the speed raises with.
+    pub driver: SpeedDriver,
+}
+
+/// The progression of the Normal difficulty : the snake speeds up by 20 pixels
+/// per second each time 5 bonuses are eaten.
+impl Default for SpeedProgression {
+    fn default() -> Self {
+        Self {
+            base: SNAKE_SPEED_FACTOR,
+            curve: SpeedCurve::Step {
+                every: 5f32,
+                increase: 20f32,
+            },
+            driver: SpeedDriver::default(),
+        }
+    }
+}
+
+impl SpeedProgression {
+    /// Returns the snake speed for the given number of bonuses eaten and time
+    /// played, in seconds.
+    ///
+    /// The speed never goes beyond [`SNAKE_MAX_SPEED_FACTOR`], so the snake
+    /// can't skip over a wall or a bonus within a single tick.
+    pub fn speed_at(&self, bonuses_eaten: u32, time: f32) -> f32 {
+        let progress = match self.driver {
+            SpeedDriver::Bonuses => bonuses_eaten as f32,
+            SpeedDriver::Time => time,
+        };
+        self.curve
+            .apply(self.base, progress)
+            .clamp(0f32, SNAKE_MAX_SPEED_FACTOR)
+    }
+
+    /// Returns the given speed as a multiple of the base speed.
+    pub fn multiplier(&self, speed: f32) -> f32 {
+        speed / self.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speed_raises_with_the_chosen_driver() {
+        let mut progression = SpeedProgression::default();
+        assert_eq!(progression.speed_at(4, 60f32), SNAKE_SPEED_FACTOR);
+        assert_eq!(progression.speed_at(5, 0f32), SNAKE_SPEED_FACTOR + 20f32);
+        progression.driver = SpeedDriver::Time;
+        assert_eq!(progression.speed_at(60, 4f32), SNAKE_SPEED_FACTOR);
+        assert_eq!(progression.speed_at(0, 5f32), SNAKE_SPEED_FACTOR + 20f32);
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt, io};
+
+/// The error that can happen while saving or loading some data kept across sessions.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// The browser local storage couldn't be accessed.
+    LocalStorage,
+    /// The saved data isn't valid.
+    Format(ron::Error),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Saved file couldn't be accessed : {}", error),
+            Self::LocalStorage => write!(f, "The local storage couldn't be accessed"),
+            Self::Format(error) => write!(f, "Saved data is invalid : {}", error),
+        }
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::Error> for StorageError {
+    fn from(error: ron::Error) -> Self {
+        Self::Format(error)
+    }
+}
+
+/// Loads the data saved on the disk under the given name, the default one if
+/// it was never saved.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> Result<T, StorageError> {
+    match std::fs::read_to_string(name) {
+        Ok(content) => Ok(ron::from_str(&content)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Saves the data on the disk under the given name.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<(), StorageError> {
+    std::fs::write(name, ron::to_string(value)?)?;
+    Ok(())
+}
+
+/// Loads the data saved in the browser local storage under the given name,
+/// the default one if it was never saved.
+#[cfg(target_arch = "wasm32")]
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> Result<T, StorageError> {
+    let content = local_storage()?
+        .get_item(name)
+        .map_err(|_| StorageError::LocalStorage)?;
+    match content {
+        Some(content) => Ok(ron::from_str(&content)?),
+        None => Ok(T::default()),
+    }
+}
+
+/// Saves the data in the browser local storage under the given name.
+#[cfg(target_arch = "wasm32")]
+pub fn save<T: Serialize>(name: &str, value: &T) -> Result<(), StorageError> {
+    local_storage()?
+        .set_item(name, &ron::to_string(value)?)
+        .map_err(|_| StorageError::LocalStorage)
+}
+
+/// Returns the local storage of the browser.
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, StorageError> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or(StorageError::LocalStorage)
+}
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+
+use crate::{
+    common::*,
+    components::prelude::{AchievementGallery, AchievementToast},
+    resources::{
+        achievements::{Achievement, AchievementTrigger, AchievementUnlocked, Achievements},
+        game_state::GameState,
+        level_editor::LevelEditor,
+        lifetime_stats::LifetimeStats,
+    },
+    world::prelude::{GameEvent, GameWorld},
+};
+
+use super::prelude::{change_system_if_inputs_pressed, is_test_playing};
+
+/// Loads the achievements unlocked in the previous sessions.
+pub fn load_achievements(mut achievements: ResMut<Achievements>) {
+    match Achievements::load() {
+        Ok(loaded) => *achievements = loaded,
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+/// Unlocks the achievements reached during the game : after a collision,
+/// as the time goes on, and once the game ended.
+///
+/// The versus games and the test-plays of the level editor don't unlock any
+/// achievement.
+pub fn unlock_achievements(
+    mut game_event_reader: EventReader<GameEvent>,
+    world: Res<GameWorld>,
+    editor: Res<Option<LevelEditor>>,
+    lifetime_stats: Res<LifetimeStats>,
+    mut achievements: ResMut<Achievements>,
+    mut unlocked_writer: EventWriter<AchievementUnlocked>,
+) {
+    if world.rival.is_some() || is_test_playing(&editor) {
+        return;
+    }
+    let mut triggers = vec![AchievementTrigger::Time];
+    for event in game_event_reader.iter() {
+        let trigger = match event {
+            GameEvent::Collision(_) => AchievementTrigger::Collision,
+            event if GameState::from_ending_event(event).is_some() => AchievementTrigger::GameEnd,
+            _ => continue,
+        };
+        if !triggers.contains(&trigger) {
+            triggers.push(trigger);
+        }
+    }
+    let unlocked: Vec<Achievement> = triggers
+        .into_iter()
+        .flat_map(|trigger| achievements.unlock(trigger, &world, &lifetime_stats))
+        .collect();
+    if unlocked.is_empty() {
+        return;
+    }
+    if let Err(error) = achievements.save() {
+        eprintln!("{}", error);
+    }
+    for achievement in unlocked {
+        unlocked_writer.send(AchievementUnlocked(achievement));
+    }
+}
+
+/// Shows a notification for each achievement unlocked, stacked at the top
+/// right of the screen.
+pub fn spawn_achievement_toasts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut unlocked_reader: EventReader<AchievementUnlocked>,
+    toast_query: Query<&AchievementToast>,
+) {
+    let shown = toast_query.iter().count();
+    for (i, AchievementUnlocked(achievement)) in unlocked_reader.iter().enumerate() {
+        commands
+            .spawn_bundle(
+                TextBundle::from_section(
+                    format!("Achievement unlocked : {}", achievement.title()),
+                    TextStyle {
+                        font: asset_server.load(FONT_ASSET_NAME),
+                        font_size: 18.0,
+                        color: Color::GOLD,
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(10.0 + (shown + i) as f32 * 25.0),
+                        right: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+            )
+            .insert(AchievementToast::default());
+    }
+}
+
+/// Fades the notifications out, and despawns them once they were shown long enough.
+pub fn fade_achievement_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut AchievementToast, &mut Text)>,
+) {
+    for (entity, mut toast, mut text) in toast_query.iter_mut() {
+        **toast += time.delta_seconds();
+        if ACHIEVEMENT_TOAST_DURATION <= **toast {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            text.sections[0]
+                .style
+                .color
+                .set_a(1f32 - **toast / ACHIEVEMENT_TOAST_DURATION);
+        }
+    }
+}
+
+/// Opens the achievements gallery when the user presses 'A'.
+pub fn open_achievements(
+    keyboard_input: ResMut<Input<KeyCode>>,
+    game_state: ResMut<State<GameState>>,
+) {
+    change_system_if_inputs_pressed(
+        GameState::Achievements,
+        vec![KeyCode::A],
+        keyboard_input,
+        game_state,
+    );
+}
+
+/// Goes back to the level picker when the user presses 'ESC'.
+pub fn close_achievements(
+    keyboard_input: ResMut<Input<KeyCode>>,
+    game_state: ResMut<State<GameState>>,
+) {
+    change_system_if_inputs_pressed(
+        GameState::Initialized,
+        vec![KeyCode::Escape],
+        keyboard_input,
+        game_state,
+    );
+}
+
+/// Spawns the gallery listing every achievement, the unlocked ones being highlighted.
+pub fn spawn_achievement_gallery(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    achievements: Res<Achievements>,
+) {
+    let font = asset_server.load(FONT_ASSET_NAME);
+    let text = |value: String, font_size: f32, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: font.clone(),
+                font_size,
+                color,
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(6.0)),
+            ..default()
+        })
+    };
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(text(
+                format!(
+                    "Achievements {}/{}",
+                    achievements.count(),
+                    Achievement::ALL.len()
+                ),
+                30.0,
+                Color::WHITE,
+            ));
+            for achievement in Achievement::ALL {
+                let color = if achievements.is_unlocked(achievement) {
+                    Color::GOLD
+                } else {
+                    Color::GRAY
+                };
+                parent.spawn_bundle(text(
+                    format!("{} : {}", achievement.title(), achievement.description()),
+                    18.0,
+                    color,
+                ));
+            }
+            parent.spawn_bundle(text("Press 'ESC' to go back.".into(), 18.0, Color::WHITE));
+        })
+        .insert(AchievementGallery);
+}
+
+/// Despawns the achievements gallery once the user leaves it.
+pub fn despawn_achievement_gallery(
+    mut commands: Commands,
+    query: Query<Entity, With<AchievementGallery>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,6 +1,14 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
 
-use crate::{components::prelude::{Border, SnakeDirection}, resources::game_state::GameState};
+use crate::{
+    common::BONUS_DIAMETER,
+    components::prelude::{BonusId, Border, ObstacleId, Portal, Snake, Spawnable},
+    resources::{
+        game_settings::PlayMode, game_state::GameState, level_editor::LevelEditor,
+        simulation_clock::SimulationClock,
+    },
+    world::prelude::{Aabb, BonusState, GameWorld, ObstacleShape, Player, SnakeDirection},
+};
 
 /// Changes the border visibility when the game is paused or resumed.
 pub fn compute_borders_visibility(
@@ -15,34 +23,206 @@ pub fn compute_borders_visibility(
 
 /// Change the current system to the target system if any of the keys
 /// passed in inputs are pressed.
+///
+/// Nothing happens if another system already queued a state change on this
+/// frame, the keys are then left for the next frame.
 pub(crate) fn change_system_if_inputs_pressed(
     target_state: GameState,
     inputs: Vec<KeyCode>,
     mut keyboard_input: ResMut<Input<KeyCode>>,
     mut game_state: ResMut<State<GameState>>,
 ) {
-    if keyboard_input.any_just_pressed(inputs.clone()) {
-        game_state.set(target_state).unwrap();
+    if keyboard_input.any_just_pressed(inputs.clone()) && game_state.set(target_state).is_ok() {
         for input in inputs {
             keyboard_input.reset(input);
         }
     }
 }
 
-pub (crate) fn get_direction_from_input(
-    keyboard_input: Res<Input<KeyCode>>) -> Option<SnakeDirection> {
-    let mut direction : Option<SnakeDirection> = None;
-    if keyboard_input.any_pressed([KeyCode::Right, KeyCode::D]) {
-        direction = Some(SnakeDirection::Right);
+/// Returns whether the game played is a test of the level being edited.
+pub(crate) fn is_test_playing(editor: &Option<LevelEditor>) -> bool {
+    matches!(editor, Some(editor) if editor.test_playing)
+}
+
+/// Returns the keys turning the snake of the given player toward each direction.
+///
+/// A single snake is played with both the arrows and ZQSD, while in a versus
+/// game the first player has the arrows and the second one WASD or ZQSD.
+fn direction_keys(
+    play_mode: PlayMode,
+    player: Player,
+) -> [(&'static [KeyCode], SnakeDirection); 4] {
+    match (play_mode, player) {
+        (PlayMode::Solo, _) => [
+            (&[KeyCode::Right, KeyCode::D], SnakeDirection::Right),
+            (&[KeyCode::Left, KeyCode::Q], SnakeDirection::Left),
+            (&[KeyCode::Up, KeyCode::Z], SnakeDirection::Up),
+            (&[KeyCode::Down, KeyCode::S], SnakeDirection::Down),
+        ],
+        (PlayMode::Versus, Player::One) => [
+            (&[KeyCode::Right], SnakeDirection::Right),
+            (&[KeyCode::Left], SnakeDirection::Left),
+            (&[KeyCode::Up], SnakeDirection::Up),
+            (&[KeyCode::Down], SnakeDirection::Down),
+        ],
+        (PlayMode::Versus, Player::Two) => [
+            (&[KeyCode::D], SnakeDirection::Right),
+            (&[KeyCode::A, KeyCode::Q], SnakeDirection::Left),
+            (&[KeyCode::W, KeyCode::Z], SnakeDirection::Up),
+            (&[KeyCode::S], SnakeDirection::Down),
+        ],
+    }
+}
+
+/// Returns the turns the given player requested on this frame, by pressing
+/// their direction keys.
+pub(crate) fn get_turns_from_input(
+    keyboard_input: &Input<KeyCode>,
+    play_mode: PlayMode,
+    player: Player,
+) -> Vec<SnakeDirection> {
+    direction_keys(play_mode, player)
+        .into_iter()
+        .filter(|(keys, _)| keyboard_input.any_just_pressed(keys.iter().copied()))
+        .map(|(_, direction)| direction)
+        .collect()
+}
+
+/// Returns the direction held by the given player, the last one of the
+/// direction keys if several are held.
+pub(crate) fn get_direction_from_input(
+    keyboard_input: &Input<KeyCode>,
+    play_mode: PlayMode,
+    player: Player,
+) -> Option<SnakeDirection> {
+    direction_keys(play_mode, player)
+        .into_iter()
+        .rev()
+        .find(|(keys, _)| keyboard_input.any_pressed(keys.iter().copied()))
+        .map(|(_, direction)| direction)
+}
+
+/// Spawns the entity rendering a bonus of the game world.
+pub(crate) fn spawn_bonus(
+    bonus: &BonusState,
+    commands: &mut Commands,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+) {
+    let bonus_entity = bonus.kind.spawn(
+        Transform::default()
+            .with_scale(Vec3::splat(BONUS_DIAMETER))
+            .with_translation(bonus.position),
+        commands,
+        materials,
+        meshes,
+    );
+    commands.entity(bonus_entity).insert(BonusId(bonus.id));
+}
+
+/// Returns the bundle of a border, a unit square scaled by its transform.
+fn get_border_bundle(
+    transform: Transform,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+) -> MaterialMesh2dBundle<ColorMaterial> {
+    MaterialMesh2dBundle {
+        mesh: meshes
+            .add(Mesh::from(shape::Quad {
+                size: Vec2::splat(1f32),
+                flip: false,
+            }))
+            .into(),
+        transform,
+        material: materials.add(ColorMaterial::from(Color::WHITE)),
+        ..default()
+    }
+}
+
+/// Returns the transform rendering an obstacle shape.
+pub(crate) fn get_obstacle_transform(shape: &ObstacleShape) -> Transform {
+    Transform::default()
+        .with_translation(shape.center)
+        .with_rotation(Quat::from_rotation_z(shape.angle))
+        .with_scale(shape.size.extend(0f32))
+}
+
+/// Spawns the entities rendering the borders of the game world.
+pub(crate) fn spawn_borders(
+    borders: &[Aabb],
+    commands: &mut Commands,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+) {
+    for border in borders {
+        let transform = Transform::default()
+            .with_translation(border.center)
+            .with_scale(border.size.extend(0f32));
+        commands
+            .spawn()
+            .insert_bundle(get_border_bundle(transform, materials, meshes))
+            .insert(Border);
     }
-    if keyboard_input.any_pressed([KeyCode::Left, KeyCode::Q]) {
-        direction = Some(SnakeDirection::Left);
+}
+
+/// Spawns the entities rendering the obstacles of the game world, which are
+/// borders that move.
+pub(crate) fn spawn_obstacles(
+    obstacles: &[ObstacleShape],
+    commands: &mut Commands,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+) {
+    for (id, obstacle) in obstacles.iter().enumerate() {
+        let transform = get_obstacle_transform(obstacle);
+        commands
+            .spawn()
+            .insert_bundle(get_border_bundle(transform, materials, meshes))
+            .insert(Border)
+            .insert(ObstacleId(id));
+    }
+}
+
+/// Spawns the entities rendering a new game world, and inserts it as a resource
+/// along with a fresh simulation clock.
+pub(crate) fn spawn_game_world(
+    world: GameWorld,
+    commands: &mut Commands,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    meshes: &mut ResMut<Assets<Mesh>>,
+) {
+    // Spawn the snakes
+    let rival = world.rival.iter().map(|rival| (Player::Two, &rival.snake));
+    for (player, snake) in std::iter::once((Player::One, &world.snake)).chain(rival) {
+        Snake(player).spawn(
+            Transform::default().with_translation(snake.position),
+            commands,
+            materials,
+            meshes,
+        );
     }
-    if keyboard_input.any_pressed([KeyCode::Up, KeyCode::Z]) {
-        direction = Some(SnakeDirection::Up);
+    // The first bonus
+    for bonus in world.bonuses.iter() {
+        spawn_bonus(bonus, commands, materials, meshes);
     }
-    if keyboard_input.any_pressed([KeyCode::Down, KeyCode::S]) {
-        direction = Some(SnakeDirection::Down);
+    spawn_borders(&world.borders, commands, materials, meshes);
+    spawn_obstacles(
+        &world.interpolated_obstacle_shapes(0f32),
+        commands,
+        materials,
+        meshes,
+    );
+    for (pair, (a, b)) in world.portals.iter().enumerate() {
+        for position in [a, b] {
+            Portal { pair }.spawn(
+                // The portals are drawn below the snake.
+                Transform::default().with_translation(position.truncate().extend(-1f32)),
+                commands,
+                materials,
+                meshes,
+            );
+        }
     }
-    direction
+    commands.insert_resource(world);
+    commands.insert_resource(SimulationClock::default());
 }
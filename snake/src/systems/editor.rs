@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::{
+    common::*,
+    components::prelude::{Border, SpawnMarker, Spawnable},
+    resources::{
+        game_state::GameState,
+        level::{Level, LevelHandles},
+        level_editor::LevelEditor,
+    },
+};
+
+use super::prelude::{change_system_if_inputs_pressed, spawn_borders};
+
+/// Filter matching every entity spawned by the level editor.
+type EditorComponents = Or<(With<Border>, With<SpawnMarker>)>;
+
+/// Opens the level editor when the user presses 'E'.
+pub fn open_editor(keyboard_input: ResMut<Input<KeyCode>>, game_state: ResMut<State<GameState>>) {
+    change_system_if_inputs_pressed(
+        GameState::Editor,
+        vec![KeyCode::E],
+        keyboard_input,
+        game_state,
+    );
+}
+
+/// Starts editing a new level, unless one is already being edited.
+pub fn start_editor(mut editor: ResMut<Option<LevelEditor>>, levels: Res<Assets<Level>>) {
+    match &mut *editor {
+        Some(editor) => editor.test_playing = false,
+        None => *editor = Some(LevelEditor::new(free_custom_level_number(&levels))),
+    }
+}
+
+/// Returns the lowest number no custom level is named after.
+fn free_custom_level_number(levels: &Assets<Level>) -> u32 {
+    (1..)
+        .find(|number| {
+            let name = format!("Custom {}", number);
+            !levels.iter().any(|(_, level)| level.name == name)
+        })
+        .unwrap_or_default()
+}
+
+/// Edits the level with the mouse : the walls are drawn, moved or resized by
+/// dragging them, and deleted with a right click. The spawn is set with 'Space'
+/// and the screen edges behavior changed with 'W'.
+pub fn edit_level(
+    mouse_input: Res<Input<MouseButton>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    mut editor: ResMut<Option<LevelEditor>>,
+) {
+    let Some(cursor) = get_cursor_position(&windows) else {
+        return;
+    };
+    let is_dragging = matches!(&*editor, Some(editor) if editor.is_dragging());
+    // The editor is only borrowed mutably when edited, so it is rendered again
+    // only when it changed.
+    let is_editing = mouse_input.any_just_pressed([MouseButton::Left, MouseButton::Right])
+        || mouse_input.just_released(MouseButton::Left)
+        || is_dragging
+        || keyboard_input.any_just_pressed([KeyCode::Space, KeyCode::W]);
+    if !is_editing {
+        return;
+    }
+    let Some(editor) = &mut *editor else {
+        return;
+    };
+    if mouse_input.just_pressed(MouseButton::Left) {
+        let resize = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+        editor.start_drag(cursor, resize);
+    } else if mouse_input.just_released(MouseButton::Left) {
+        editor.end_drag();
+    } else if mouse_input.pressed(MouseButton::Left) && editor.is_dragging() {
+        editor.drag_to(cursor);
+    }
+    if mouse_input.just_pressed(MouseButton::Right) && !editor.is_dragging() {
+        editor.delete_wall_at(cursor);
+    }
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        editor.set_spawn(cursor);
+    }
+    if keyboard_input.just_pressed(KeyCode::W) {
+        editor.toggle_edges();
+    }
+}
+
+/// Returns the cursor position in the game coordinates, the origin being the
+/// center of the screen.
+fn get_cursor_position(windows: &Windows) -> Option<Vec2> {
+    let window = windows.get_primary()?;
+    let cursor = window.cursor_position()?;
+    Some(cursor - Vec2::new(window.width(), window.height()) / 2f32)
+}
+
+/// Handles the editor commands : 'T' test-plays the level, 'Enter' saves it,
+/// 'N' starts a new one, and 'ESC' goes back to the level picker.
+pub fn editor_commands(
+    keyboard_input: ResMut<Input<KeyCode>>,
+    mut game_state: ResMut<State<GameState>>,
+    mut editor: ResMut<Option<LevelEditor>>,
+    mut level: ResMut<Option<Level>>,
+    mut levels: ResMut<Assets<Level>>,
+    mut level_handles: ResMut<LevelHandles>,
+) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        if let Some(editor) = &mut *editor {
+            if game_state.set(GameState::Ready).is_ok() {
+                editor.test_playing = true;
+                *level = Some(editor.level.clone());
+            }
+        }
+    } else if keyboard_input.just_pressed(KeyCode::Return) {
+        if let Some(editor) = &mut *editor {
+            save_level(editor);
+            match &editor.handle {
+                Some(handle) => {
+                    if let Some(saved_level) = levels.get_mut(handle) {
+                        *saved_level = editor.level.clone();
+                    }
+                }
+                None => {
+                    let handle = levels.add(editor.level.clone());
+                    level_handles.push(handle.clone());
+                    editor.handle = Some(handle);
+                }
+            }
+        }
+    } else if keyboard_input.just_pressed(KeyCode::N) {
+        *editor = Some(LevelEditor::new(free_custom_level_number(&levels)));
+    } else {
+        change_system_if_inputs_pressed(
+            GameState::Initialized,
+            vec![KeyCode::Escape],
+            keyboard_input,
+            game_state,
+        );
+    }
+}
+
+/// Saves the edited level in the custom levels folder, so it is loaded on the next start.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_level(editor: &LevelEditor) {
+    if let Err(error) = std::fs::create_dir_all(CUSTOM_LEVELS_FOLDER) {
+        eprintln!("Custom levels folder couldn't be created : {}", error);
+        return;
+    }
+    let path = std::path::Path::new(CUSTOM_LEVELS_FOLDER).join(&editor.file_name);
+    if let Err(error) = editor.level.save(&path.to_string_lossy()) {
+        eprintln!("{}", error);
+    }
+}
+
+/// The browser build has no file system, the level is only kept until the page is closed.
+#[cfg(target_arch = "wasm32")]
+fn save_level(_editor: &LevelEditor) {}
+
+/// Renders the walls and the spawn of the edited level, each time it changes.
+pub fn render_editor(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    editor: Res<Option<LevelEditor>>,
+    editor_components: Query<Entity, EditorComponents>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+    let Some(editor) = &*editor else {
+        return;
+    };
+    for entity in editor_components.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_borders(
+        &editor.level.get_borders(),
+        &mut commands,
+        &mut materials,
+        &mut meshes,
+    );
+    SpawnMarker.spawn(
+        Transform::default().with_translation(editor.level.get_snake_initial_position()),
+        &mut commands,
+        &mut materials,
+        &mut meshes,
+    );
+}
+
+/// Despawns the entities of the level editor once it is left.
+pub fn close_editor(mut commands: Commands, editor_components: Query<Entity, EditorComponents>) {
+    for entity in editor_components.iter() {
+        commands.entity(entity).despawn();
+    }
+}
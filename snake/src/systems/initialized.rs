@@ -1,11 +1,88 @@
 use crate::common::*;
-use crate::resources::border_set::BorderSet;
+use crate::components::prelude::{DifficultyButton, HighScorePanel, LevelButton};
+use crate::resources::difficulty::{CustomSetting, Difficulty};
+use crate::resources::game_settings::GameSettings;
 use crate::resources::game_state::GameState;
+use crate::resources::high_scores::HighScores;
+use crate::resources::level::{Level, LevelHandles};
 use bevy::prelude::*;
 
-/// Spawn the border picker buttons.
-pub fn spawn_border_set_buttons(mut commands: Commands, asset_server: Res<AssetServer>) {
-    for border_set_variant in BorderSet::iterator() {
+/// Filter matching every entity of the level picker menu.
+type MenuEntities = Or<(
+    With<LevelButton>,
+    With<DifficultyButton>,
+    With<HighScorePanel>,
+)>;
+
+/// Loads every level of the levels folder, followed by the ones saved from
+/// the level editor.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_levels(
+    asset_server: Res<AssetServer>,
+    mut levels: ResMut<Assets<Level>>,
+    mut level_handles: ResMut<LevelHandles>,
+) {
+    match asset_server.load_folder(LEVELS_FOLDER) {
+        Ok(handles) => {
+            **level_handles = handles.into_iter().map(|handle| handle.typed()).collect();
+        }
+        Err(error) => eprintln!("Levels couldn't be loaded : {}", error),
+    }
+    // The folder only exists once a level has been saved from the editor.
+    let Ok(entries) = std::fs::read_dir(CUSTOM_LEVELS_FOLDER) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        match Level::load(&entry.path().to_string_lossy()) {
+            Ok(level) => level_handles.push(levels.add(level)),
+            Err(error) => eprintln!("{} couldn't be loaded : {}", entry.path().display(), error),
+        }
+    }
+}
+
+/// The browser build can't list the levels folder, so only the levels shipped
+/// with the game are loaded.
+#[cfg(target_arch = "wasm32")]
+pub fn load_levels(asset_server: Res<AssetServer>, mut level_handles: ResMut<LevelHandles>) {
+    **level_handles = BUILTIN_LEVELS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+}
+
+/// Spawn the level picker buttons, sorted by the levels order, followed by
+/// the generated maze one.
+///
+/// The levels are loaded in the background, so the buttons are spawned again
+/// each time a level becomes available.
+pub fn spawn_border_set_buttons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    levels: Res<Assets<Level>>,
+    level_handles: Res<LevelHandles>,
+    button_query: Query<Entity, With<LevelButton>>,
+) {
+    let mut loaded_levels: Vec<(&Handle<Level>, &Level)> = level_handles
+        .iter()
+        .filter_map(|handle| levels.get(handle).map(|level| (handle, level)))
+        .collect();
+    if loaded_levels.len() + 1 == button_query.iter().count() {
+        return;
+    }
+    for button_entity in button_query.iter() {
+        commands.entity(button_entity).despawn_recursive();
+    }
+    loaded_levels.sort_by(|(_, level), (_, other)| {
+        level
+            .order
+            .cmp(&other.order)
+            .then_with(|| level.name.cmp(&other.name))
+    });
+    let buttons = loaded_levels
+        .into_iter()
+        .map(|(handle, level)| (level.to_string(), LevelButton::File(handle.clone())))
+        .chain([("Generated".to_string(), LevelButton::Generated)]);
+    for (label, button) in buttons {
         commands
             .spawn()
             .insert_bundle(ButtonBundle {
@@ -21,7 +98,7 @@ pub fn spawn_border_set_buttons(mut commands: Commands, asset_server: Res<AssetS
             })
             .with_children(|parent| {
                 parent.spawn_bundle(TextBundle::from_section(
-                    border_set_variant.to_string(),
+                    label,
                     TextStyle {
                         font: asset_server.load(FONT_ASSET_NAME),
                         font_size: 20.0,
@@ -29,23 +106,31 @@ pub fn spawn_border_set_buttons(mut commands: Commands, asset_server: Res<AssetS
                     },
                 ));
             })
-            .insert(border_set_variant);
+            .insert(button);
     }
 }
 
 /// The interactions with the button system.
 pub fn border_set_choose_system(
-    mut button_query: Query<(&Interaction, &mut UiColor, &BorderSet)>,
+    mut button_query: Query<(&Interaction, &mut UiColor, &LevelButton)>,
     mut game_state: ResMut<State<GameState>>,
-    mut border_set: ResMut<Option<BorderSet>>,
+    levels: Res<Assets<Level>>,
+    settings: Res<GameSettings>,
+    mut level: ResMut<Option<Level>>,
 ) {
     for (interaction, mut color, button) in button_query.iter_mut() {
         match *interaction {
             Interaction::Clicked => {
-                // Each button corresponds to a border set, so we will
-                // set the clicked border set as a resource
-                *border_set = Some(*button);
-                game_state.set(GameState::Ready).unwrap();
+                // Another menu shortcut may have been pressed on the same frame.
+                if game_state.set(GameState::Ready).is_err() {
+                    break;
+                }
+                // Each button corresponds to a level, so we will
+                // set the clicked level as a resource
+                *level = match button {
+                    LevelButton::File(handle) => levels.get(handle).cloned(),
+                    LevelButton::Generated => Some(Level::generate(settings.seed)),
+                };
                 break;
             }
             Interaction::Hovered => {
@@ -58,8 +143,216 @@ pub fn border_set_choose_system(
     }
 }
 
-/// Deletes the buttons, once they aren't useful anymore, and once a border set is picked.
-pub fn delete_buttons(mut commands: Commands, mut button_query: Query<Entity, With<BorderSet>>) {
+/// Spawns the difficulty picker buttons, in a column on the left of the screen.
+pub fn spawn_difficulty_buttons(mut commands: Commands, asset_server: Res<AssetServer>) {
+    for (i, difficulty) in Difficulty::ALL.into_iter().enumerate() {
+        commands
+            .spawn()
+            .insert_bundle(ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(110.0), Val::Px(40.0)),
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(10.0),
+                        top: Val::Px(10.0 + i as f32 * 50.0),
+                        ..default()
+                    },
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: NORMAL_BUTTON.into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle::from_section(
+                    difficulty.to_string(),
+                    TextStyle {
+                        font: asset_server.load(FONT_ASSET_NAME),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ));
+            })
+            .insert(DifficultyButton(difficulty));
+    }
+}
+
+/// Applies the clicked difficulty to the settings, the selected one being highlighted.
+pub fn difficulty_choose_system(
+    mut button_query: Query<(&Interaction, &mut UiColor, &DifficultyButton)>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, mut color, button) in button_query.iter_mut() {
+        if let Interaction::Clicked = interaction {
+            if settings.difficulty != **button {
+                button.apply(&mut settings);
+            }
+        }
+        *color = match interaction {
+            _ if settings.difficulty == **button => SELECTED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            _ => NORMAL_BUTTON.into(),
+        };
+    }
+}
+
+/// Spawns the text showing the high scores of the hovered level, at the
+/// bottom right of the screen.
+pub fn spawn_high_score_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server.load(FONT_ASSET_NAME),
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(HighScorePanel);
+}
+
+/// Shows the high scores of the hovered level with the chosen difficulty.
+///
+/// The generated mazes share their high scores, whatever their seed.
+pub fn show_level_high_scores(
+    button_query: Query<(&Interaction, &LevelButton)>,
+    levels: Res<Assets<Level>>,
+    settings: Res<GameSettings>,
+    high_scores: Res<HighScores>,
+    mut panel_query: Query<&mut Text, With<HighScorePanel>>,
+) {
+    let hovered_level = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .and_then(|(_, button)| match button {
+            LevelButton::File(handle) => levels
+                .get(handle)
+                .map(|level| level.high_scores_name().to_string()),
+            LevelButton::Generated => Some(GENERATED_LEVELS_HIGH_SCORES_NAME.to_string()),
+        });
+    for mut text in panel_query.iter_mut() {
+        text.sections[0].value = hovered_level
+            .as_ref()
+            .map(|level| high_scores.table_text(level, settings.difficulty))
+            .unwrap_or_default();
+    }
+}
+
+/// Switches the movement mode when the user presses 'G'.
+pub fn movement_mode_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::G) {
+        settings.movement_mode = settings.movement_mode.toggled();
+    }
+}
+
+/// Switches to the next speed curve when the user presses 'C'.
+pub fn speed_curve_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::C) {
+        settings.speed.curve = settings.speed.curve.toggled();
+        settings.difficulty = Difficulty::Custom;
+    }
+}
+
+/// Switches what the speed raises with when the user presses 'D'.
+pub fn speed_driver_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::D) {
+        settings.speed.driver = settings.speed.driver.toggled();
+        settings.difficulty = Difficulty::Custom;
+    }
+}
+
+/// Changes a setting of the custom difficulty when the user presses its key :
+/// 'F' for the speed, 'B' for the extra bonuses, 'L' for their lifetime and
+/// 'N' for the starting length.
+pub fn custom_setting_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let keys = [
+        (KeyCode::F, CustomSetting::Speed),
+        (KeyCode::B, CustomSetting::ExtraBonuses),
+        (KeyCode::L, CustomSetting::ExtraBonusLifetime),
+        (KeyCode::N, CustomSetting::StartingLength),
+    ];
+    for (key, setting) in keys {
+        if keyboard_input.just_pressed(key) {
+            setting.cycle(&mut settings);
+        }
+    }
+}
+
+/// Switches the rule of the walls when the user presses 'K'.
+pub fn wall_rule_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::K) {
+        settings.wall_rule = settings.wall_rule.toggled();
+        settings.difficulty = Difficulty::Custom;
+    }
+}
+
+/// Switches between a solo game and a versus game when the user presses 'T'.
+pub fn play_mode_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        settings.play_mode = settings.play_mode.toggled();
+    }
+}
+
+/// Edits the seed with the digits typed by the user, 'Backspace' removes the last one.
+pub fn seed_input_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let digit_keys = [
+        [KeyCode::Key0, KeyCode::Numpad0],
+        [KeyCode::Key1, KeyCode::Numpad1],
+        [KeyCode::Key2, KeyCode::Numpad2],
+        [KeyCode::Key3, KeyCode::Numpad3],
+        [KeyCode::Key4, KeyCode::Numpad4],
+        [KeyCode::Key5, KeyCode::Numpad5],
+        [KeyCode::Key6, KeyCode::Numpad6],
+        [KeyCode::Key7, KeyCode::Numpad7],
+        [KeyCode::Key8, KeyCode::Numpad8],
+        [KeyCode::Key9, KeyCode::Numpad9],
+    ];
+    for (digit, keys) in digit_keys.into_iter().enumerate() {
+        if keyboard_input.any_just_pressed(keys) {
+            settings.push_seed_digit(digit as u64);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        settings.pop_seed_digit();
+    }
+}
+
+/// Deletes the buttons and the high scores, once they aren't useful anymore,
+/// and once a level is picked.
+pub fn delete_buttons(mut commands: Commands, mut button_query: Query<Entity, MenuEntities>) {
     for button_entity in button_query.iter_mut() {
         commands.entity(button_entity).despawn_recursive();
     }
@@ -1,15 +1,21 @@
+pub mod achievements;
 pub mod common;
+pub mod editor;
 pub mod initialized;
 pub mod over;
 pub mod paused;
 pub mod ready;
+pub mod replay;
 pub mod running;
 
 pub mod prelude {
+    pub use super::achievements::*;
     pub use super::common::*;
+    pub use super::editor::*;
     pub use super::initialized::*;
     pub use super::over::*;
     pub use super::paused::*;
     pub use super::ready::*;
+    pub use super::replay::*;
     pub use super::running::*;
 }
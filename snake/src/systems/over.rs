@@ -1,18 +1,333 @@
 use bevy::prelude::*;
 
-use crate::resources::{game_state::GameState, score::Score};
+use crate::{
+    common::*,
+    components::prelude::{
+        Bonus, Border, EffectIndicator, GameSummary, Portal, Queue, Snake, SummaryText,
+    },
+    resources::{
+        game_state::GameState, high_scores::HighScores, level_editor::LevelEditor,
+        lifetime_stats::LifetimeStats, score::Score,
+    },
+    world::prelude::{BonusCounts, GameWorld, Player, Rival},
+};
 
-/// Restarts the game when it is over.
+use super::prelude::is_test_playing;
+
+/// Filter matching every entity spawned for a game.
+type GameComponents = Or<(
+    With<Snake>,
+    With<Queue>,
+    With<Bonus>,
+    With<Border>,
+    With<Portal>,
+    With<EffectIndicator>,
+)>;
+
+/// Despawns all the game components once the game is over or won.
+pub fn despawn_game_components(
+    mut commands: Commands,
+    game_components: Query<Entity, GameComponents>,
+) {
+    for entity in game_components.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Restarts the game when it is over or won.
+///
+/// A test-played level goes back to the level editor instead of the level picker.
+/// Nothing happens while the name of a record is being typed, or if watching
+/// the replay has been requested on the same frame.
 pub fn restart_game(
     keyboard_input: Res<Input<KeyCode>>,
     mut score: ResMut<Score>,
     mut game_state: ResMut<State<GameState>>,
+    editor: Res<Option<LevelEditor>>,
+    high_scores: Res<HighScores>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::R) {
-        score.0 = 0;
-        game_state.set(GameState::Ready).unwrap();
+    if high_scores.pending.is_some() {
+        return;
+    }
+    let target_state = if keyboard_input.just_pressed(KeyCode::R) {
+        GameState::Ready
     } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        if is_test_playing(&editor) {
+            GameState::Editor
+        } else {
+            GameState::Initialized
+        }
+    } else {
+        return;
+    };
+    if game_state.set(target_state).is_ok() {
         score.0 = 0;
-        game_state.set(GameState::Initialized).unwrap();
     }
 }
+
+/// Loads the high scores saved by the previous games.
+pub fn load_high_scores(mut high_scores: ResMut<HighScores>) {
+    match HighScores::load() {
+        Ok(loaded) => *high_scores = loaded,
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+/// Loads the statistics of the games played in the previous sessions.
+pub fn load_lifetime_stats(mut lifetime_stats: ResMut<LifetimeStats>) {
+    match LifetimeStats::load() {
+        Ok(loaded) => *lifetime_stats = loaded,
+        Err(error) => eprintln!("{}", error),
+    }
+}
+
+/// Forgets the characters typed while playing, so the steering keys pressed
+/// right before the end of the game don't end up in the name of a record.
+pub fn clear_typed_characters(mut received_characters: ResMut<Events<ReceivedCharacter>>) {
+    received_characters.clear();
+}
+
+/// Edits the name of the record beaten with the characters typed by the user,
+/// 'Backspace' removes the last one and 'Enter' saves the record.
+pub fn enter_record_name(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let record = match &mut high_scores.pending {
+        Some(record) => record,
+        None => return,
+    };
+    for event in received_characters.iter() {
+        let is_name_character = event.char.is_alphanumeric() || event.char == ' ';
+        if is_name_character && record.name.chars().count() < HIGH_SCORE_NAME_MAX_LENGTH {
+            record.name.push(event.char);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        record.name.pop();
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter])
+        && high_scores.confirm_record()
+    {
+        if let Err(error) = high_scores.save() {
+            eprintln!("{}", error);
+        }
+    }
+}
+
+/// Spawns the screen summing up the game : how it ended, the detail of its
+/// score, its statistics along with the lifetime ones, and the high scores.
+pub fn spawn_game_summary(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(FONT_ASSET_NAME);
+    let text = |font_size: f32| {
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font.clone(),
+                font_size,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_alignment(TextAlignment::CENTER)
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.0)),
+            ..default()
+        })
+    };
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(text(26.0)).insert(SummaryText::Title);
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::FlexStart,
+                        ..default()
+                    },
+                    color: Color::NONE.into(),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for section in [
+                        SummaryText::Score,
+                        SummaryText::Game,
+                        SummaryText::Lifetime,
+                        SummaryText::HighScores,
+                    ] {
+                        row.spawn_bundle(text(14.0)).insert(section);
+                    }
+                });
+            parent.spawn_bundle(text(18.0)).insert(SummaryText::Prompt);
+        })
+        .insert(GameSummary);
+}
+
+/// Fills the game summary, the prompt following the name typed for a record.
+///
+/// A versus game shows the winner and the score of each player instead.
+pub fn update_game_summary(
+    game_state: Res<State<GameState>>,
+    world: Option<Res<GameWorld>>,
+    high_scores: Res<HighScores>,
+    lifetime_stats: Res<LifetimeStats>,
+    mut query: Query<(&mut Text, &SummaryText)>,
+) {
+    let world = match world {
+        Some(world) => world,
+        None => return,
+    };
+    for (mut text, section) in query.iter_mut() {
+        text.sections[0].value = match (section, &world.rival) {
+            (SummaryText::Title, _) => match (world.outcome, game_state.current()) {
+                (Some(outcome), _) => format!(
+                    "{}\nVersus game on {}, seed {}",
+                    outcome,
+                    world.level.name,
+                    world.rng.seed()
+                ),
+                (None, GameState::Won) => format!(
+                    "Perfect game on {} !\nThe snake filled the whole board.",
+                    world.level.name
+                ),
+                _ => format!("Game over on {}", world.level.name),
+            },
+            (SummaryText::Score, Some(rival)) => player_text(&world, rival, Player::One),
+            (SummaryText::Game, Some(rival)) => player_text(&world, rival, Player::Two),
+            (SummaryText::Lifetime | SummaryText::HighScores, Some(_)) => String::default(),
+            (SummaryText::Score, None) => score_text(&world),
+            (SummaryText::Game, None) => game_stats_text(&world),
+            (SummaryText::Lifetime, None) => lifetime_stats_text(&lifetime_stats),
+            (SummaryText::HighScores, None) => high_scores_text(&world, &high_scores),
+            (SummaryText::Prompt, _) => match &high_scores.pending {
+                Some(record) => format!(
+                    "New record ! Type your name : {}_\nPress 'Enter' to save it.",
+                    record.name
+                ),
+                None => "Press 'R' to restart, 'V' to watch the replay.\nPress 'ESC' to choose another level.".into(),
+            },
+        };
+    }
+}
+
+/// Despawns the game summary once the user leaves it.
+pub fn despawn_game_summary(mut commands: Commands, query: Query<Entity, With<GameSummary>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Returns the given time in seconds as minutes and seconds, with the hours if any.
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
+
+/// Returns the lines counting each kind of bonus, indented below their total.
+fn bonus_counts_text(counts: &BonusCounts) -> String {
+    counts
+        .iter()
+        .map(|(bonus, count)| format!("\n  {} : {}", bonus, count))
+        .collect()
+}
+
+/// Returns the detail of the score of the game.
+fn score_text(world: &GameWorld) -> String {
+    let breakdown = world.score_breakdown;
+    format!(
+        "Score\nBonuses : {}\nCombos : {}\nQuickness : {}\nBest combo : x{}\nDifficulty : x{}\nTotal : {}\nSeed : {}",
+        breakdown.bonuses,
+        breakdown.combos,
+        breakdown.quickness,
+        breakdown.best_streak,
+        breakdown.multiplier,
+        world.score,
+        world.rng.seed()
+    )
+}
+
+/// Returns the detail of the score of the given player of a versus game,
+/// along with the length of their snake.
+fn player_text(world: &GameWorld, rival: &Rival, player: Player) -> String {
+    let (breakdown, score, snake) = match player {
+        Player::One => (world.score_breakdown, world.score, &world.snake),
+        Player::Two => (rival.score_breakdown, rival.score, &rival.snake),
+    };
+    format!(
+        "{}\nBonuses : {}\nCombos : {}\nQuickness : {}\nBest combo : x{}\nLength : {}\nTotal : {}",
+        player,
+        breakdown.bonuses,
+        breakdown.combos,
+        breakdown.quickness,
+        breakdown.best_streak,
+        snake.queue.len(),
+        score
+    )
+}
+
+/// Returns the statistics of the game.
+fn game_stats_text(world: &GameWorld) -> String {
+    let stats = &world.stats;
+    format!(
+        "This game\nTime : {}\nDistance : {:.0} px\nTurns : {}\nMax length : {}\nEaten : {}{}\nMissed : {}{}\nDeath : {}",
+        format_duration(stats.duration),
+        stats.distance,
+        stats.turns,
+        stats.max_length,
+        stats.bonuses_eaten.total(),
+        bonus_counts_text(&stats.bonuses_eaten),
+        stats.bonuses_missed.total(),
+        bonus_counts_text(&stats.bonuses_missed),
+        stats
+            .death_cause
+            .map(|cause| cause.to_string())
+            .unwrap_or_else(|| "none".into())
+    )
+}
+
+/// Returns the statistics of every game played.
+fn lifetime_stats_text(stats: &LifetimeStats) -> String {
+    format!(
+        "Lifetime\nGames : {} ({} won)\nTime : {}\nDistance : {:.0} px\nTurns : {}\nBest length : {}\nEaten : {}\nMissed : {}\nTop death : {}",
+        stats.games,
+        stats.wins,
+        format_duration(stats.duration),
+        stats.distance,
+        stats.turns,
+        stats.max_length,
+        stats.bonuses_eaten.total(),
+        stats.bonuses_missed.total(),
+        stats
+            .main_death_cause()
+            .map(|(cause, count)| format!("{} ({})", cause, count))
+            .unwrap_or_else(|| "none".into())
+    )
+}
+
+/// Returns the high scores of the level played with the difficulty.
+fn high_scores_text(world: &GameWorld, high_scores: &HighScores) -> String {
+    let lines: Vec<String> = high_scores
+        .table(world.level.high_scores_name(), world.settings.difficulty)
+        .iter()
+        .enumerate()
+        .map(|(i, high_score)| format!("{}. {}  {}", i + 1, high_score.name, high_score.score))
+        .collect();
+    format!(
+        "High scores\n{}\n{}",
+        world.settings.difficulty,
+        lines.join("\n")
+    )
+}
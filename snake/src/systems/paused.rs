@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::resources::game_state::GameState;
+use crate::{resources::game_state::GameState, world::prelude::GameWorld};
 
 use super::prelude::change_system_if_inputs_pressed;
 
@@ -13,3 +13,8 @@ pub fn resume_game(keyboard_input: ResMut<Input<KeyCode>>, game_state: ResMut<St
         game_state,
     );
 }
+
+/// Records the pause in the replay of the game.
+pub fn record_pause(mut world: ResMut<GameWorld>) {
+    world.pause();
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::{
+    common::*,
+    resources::{
+        game_state::GameState, high_scores::HighScores, level::Level, score::Score,
+        simulation_clock::SimulationClock,
+    },
+    world::prelude::{GameWorld, Replay, ReplayPlayer},
+};
+
+use super::prelude::{change_system_if_inputs_pressed, spawn_game_world};
+
+/// Loads the replay of the last game saved on the disk, if any.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_last_replay(mut last_replay: ResMut<Option<Replay>>) {
+    if std::path::Path::new(REPLAY_FILE_NAME).exists() {
+        match Replay::load(REPLAY_FILE_NAME) {
+            Ok(replay) => *last_replay = Some(replay),
+            Err(error) => eprintln!("{}", error),
+        }
+    }
+}
+
+/// The browser build has no file system, so there is no replay to load.
+#[cfg(target_arch = "wasm32")]
+pub fn load_last_replay() {}
+
+/// Starts watching the last replay when the user presses 'V', unless the name
+/// of a record is being typed.
+pub fn watch_replay(
+    keyboard_input: ResMut<Input<KeyCode>>,
+    game_state: ResMut<State<GameState>>,
+    last_replay: Res<Option<Replay>>,
+    high_scores: Res<HighScores>,
+) {
+    if last_replay.is_some() && high_scores.pending.is_none() {
+        change_system_if_inputs_pressed(
+            GameState::Replay,
+            vec![KeyCode::V],
+            keyboard_input,
+            game_state,
+        );
+    }
+}
+
+/// Creates the game world of the replay, and starts it with the recorded first direction.
+pub fn start_replay(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    last_replay: Res<Option<Replay>>,
+    mut level: ResMut<Option<Level>>,
+    mut score: ResMut<Score>,
+) {
+    if let Some(replay) = &*last_replay {
+        let mut world = GameWorld::new(replay.level.clone(), replay.game_settings());
+        if let Some(first_direction) = replay.first_direction {
+            world.start(first_direction);
+        }
+        // Restarting once the replay is over happens on the same level.
+        *level = Some(replay.level.clone());
+        **score = 0;
+        spawn_game_world(world, &mut commands, &mut materials, &mut meshes);
+        commands.insert_resource(ReplayPlayer::new(replay.clone()));
+    }
+}
+
+/// Advances the game world with the inputs of the replay.
+///
+/// The replay ends once the game does.
+pub fn step_replay(
+    time: Res<Time>,
+    mut clock: ResMut<SimulationClock>,
+    mut world: ResMut<GameWorld>,
+    mut player: ResMut<ReplayPlayer>,
+    mut game_state: ResMut<State<GameState>>,
+) {
+    for _ in 0..clock.advance(time.delta_seconds()) {
+        let input = player.input_at(world.tick);
+        let events = world.step(input);
+        if let Some(end_state) = events.iter().find_map(GameState::from_ending_event) {
+            // The end of the replay takes precedence over a stop requested on the same frame.
+            game_state.overwrite_set(end_state).unwrap();
+            break;
+        }
+    }
+}
+
+/// Stops the replay when the user presses 'ESC'.
+pub fn stop_replay(keyboard_input: ResMut<Input<KeyCode>>, game_state: ResMut<State<GameState>>) {
+    change_system_if_inputs_pressed(
+        GameState::Over,
+        vec![KeyCode::Escape],
+        keyboard_input,
+        game_state,
+    );
+}
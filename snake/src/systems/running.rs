@@ -2,98 +2,290 @@ use bevy::prelude::*;
 
 use crate::{
     common::*,
-    components::prelude::{Bonus, Collider, Queue, Snake},
-    resources::game_state::GameState,
-    CollisionEvent, ExtraBonusTimer,
+    components::prelude::{Bonus, BonusId, EffectIndicator, ObstacleId, Queue, Snake, Spawnable},
+    resources::{
+        game_state::GameState, high_scores::HighScores, level_editor::LevelEditor,
+        lifetime_stats::LifetimeStats, score::Score, simulation_clock::SimulationClock,
+    },
+    world::prelude::{GameEvent, GameInput, GameWorld, Player, Replay},
 };
 
-use super::prelude::{change_system_if_inputs_pressed, get_direction_from_input};
+use super::prelude::{
+    change_system_if_inputs_pressed, get_obstacle_transform, get_turns_from_input, is_test_playing,
+    spawn_bonus,
+};
 
-/// The movement of snake per TIME_STEP applied to the ball.
-pub fn move_snake(mut query: Query<(&mut Transform, &mut Snake)>, time: Res<Time>) {
-    let (mut transform, mut snake) = query.single_mut();
-    if let Some(direction) = snake.direction {
-        let translation_diff = direction.into_translation() * SNAKE_SPEED_FACTOR * time.delta_seconds();
-        snake.last_position = transform.translation;
-        let mut new_translation = transform.translation + translation_diff;
-        // Upper or lower component translation when there is no border
-        if MAX_SCREEN_WIDTH < f32::abs(new_translation.x) {
-            new_translation.x = -MAX_SCREEN_WIDTH * new_translation.x.signum();
-        }
-        // Upper or lower component translation when there is no border
-        if MAX_SCREEN_HEIGHT < f32::abs(new_translation.y) {
-            new_translation.y = -MAX_SCREEN_HEIGHT * new_translation.y.signum();
+/// Advances the game world by as many fixed ticks as the time elapsed since
+/// the last frame allows, with the user input.
+pub fn step_world(
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut clock: ResMut<SimulationClock>,
+    mut world: ResMut<GameWorld>,
+    mut game_state: ResMut<State<GameState>>,
+    mut game_event_writer: EventWriter<GameEvent>,
+    mut pending_input: Local<GameInput>,
+) {
+    // The turns are kept until a tick happens, as the frame rate can be higher
+    // than the tick rate.
+    let play_mode = world.settings.play_mode;
+    pending_input.turns.extend(get_turns_from_input(
+        &keyboard_input,
+        play_mode,
+        Player::One,
+    ));
+    if world.rival.is_some() {
+        pending_input.rival_turns.extend(get_turns_from_input(
+            &keyboard_input,
+            play_mode,
+            Player::Two,
+        ));
+    }
+    let ticks = clock.advance(time.delta_seconds());
+    let events: Vec<GameEvent> = (0..ticks)
+        .flat_map(|_| world.step(std::mem::take(&mut *pending_input)))
+        .collect();
+    for event in events {
+        if let Some(end_state) = GameState::from_ending_event(&event) {
+            // The end of the game takes precedence over a pause requested on the same frame.
+            game_state.overwrite_set(end_state).unwrap();
         }
-        transform.translation = new_translation;
+        game_event_writer.send(event);
     }
 }
 
-/// Moves the queue of the snake, where n+1 position = n position and 0 = snake's last position
-pub fn move_queue(mut query: Query<&mut Transform, With<Queue>>, snake: Query<&Snake>) {
-    let snake = snake.single();
-    let mut last_position = snake.last_position;
-    for mut transform in query.iter_mut() {
-        std::mem::swap(&mut transform.translation, &mut last_position)
+/// Keeps the replay of the game once it is over.
+pub fn keep_replay(
+    mut game_event_reader: EventReader<GameEvent>,
+    world: Res<GameWorld>,
+    mut last_replay: ResMut<Option<Replay>>,
+) {
+    if game_event_reader
+        .iter()
+        .any(|event| GameState::from_ending_event(event).is_some())
+    {
+        save_replay(&world.replay);
+        *last_replay = Some(world.replay.clone());
     }
 }
 
-/// Check whether the snake has collided anything, a bonus or a border.
-pub fn check_collisions(
-    snake: Query<&Transform, With<Snake>>,
-    colliders: Query<(&Transform, Option<&Bonus>), With<Collider>>,
-    mut collision_event_writer: EventWriter<CollisionEvent>,
+/// Asks for the name of the player once the game is over, if its score
+/// beats a record of the level with the difficulty.
+///
+/// The versus games and the test-plays of the level editor don't make records.
+pub fn keep_high_score(
+    mut game_event_reader: EventReader<GameEvent>,
+    world: Res<GameWorld>,
+    editor: Res<Option<LevelEditor>>,
+    mut high_scores: ResMut<HighScores>,
 ) {
-    let snake_position = snake.single().translation;
-    for (collider, maybe_bonus) in colliders.iter() {
-        let collider_dimensions = Vec2::new(collider.scale.x, collider.scale.y);
-        let collide = bevy::sprite::collide_aabb::collide(
-            snake_position,
-            SNAKE_DIMENSIONS,
-            collider.translation,
-            collider_dimensions,
+    if world.rival.is_none()
+        && !is_test_playing(&editor)
+        && game_event_reader
+            .iter()
+            .any(|event| GameState::from_ending_event(event).is_some())
+    {
+        high_scores.start_record(
+            world.level.high_scores_name(),
+            world.settings.difficulty,
+            world.score,
         );
-        if collide.is_some() {
-            if let Some(bonus) = maybe_bonus {
-                collision_event_writer.send(CollisionEvent::Bonus(bonus.get_points()));
-            } else {
-                // If the collider isn't a bonus, it is a border.
-                collision_event_writer.send_default();
+    }
+}
+
+/// Adds the statistics of the game to the lifetime ones once it is over, and
+/// saves them.
+///
+/// Only the solo games are counted, apart from the test-plays of the level editor.
+pub fn keep_lifetime_stats(
+    mut game_event_reader: EventReader<GameEvent>,
+    world: Res<GameWorld>,
+    editor: Res<Option<LevelEditor>>,
+    mut lifetime_stats: ResMut<LifetimeStats>,
+) {
+    if world.rival.is_none()
+        && !is_test_playing(&editor)
+        && game_event_reader
+            .iter()
+            .any(|event| GameState::from_ending_event(event).is_some())
+    {
+        lifetime_stats.add(&world.stats);
+        if let Err(error) = lifetime_stats.save() {
+            eprintln!("{}", error);
+        }
+    }
+}
+
+/// Saves the replay of the game on the disk, so it can be watched again
+/// once the app is restarted.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_replay(replay: &Replay) {
+    if let Err(error) = replay.save(REPLAY_FILE_NAME) {
+        eprintln!("{}", error);
+    }
+}
+
+/// The browser build has no file system, the replay is only kept in memory.
+#[cfg(target_arch = "wasm32")]
+fn save_replay(_replay: &Replay) {}
+
+/// Displays the score of the game world.
+pub fn update_score(world: Res<GameWorld>, mut score: ResMut<Score>) {
+    **score = world.score;
+}
+
+/// Renders the snake heads and their queues at their game world positions,
+/// interpolated between the last two ticks.
+pub fn render_snake(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    world: Res<GameWorld>,
+    clock: Res<SimulationClock>,
+    mut snake: Query<(&Snake, &mut Transform)>,
+    mut queue: Query<(Entity, &Queue, &mut Transform), Without<Snake>>,
+) {
+    let rival = world.rival.iter().map(|rival| (Player::Two, &rival.snake));
+    for (player, snake_state) in std::iter::once((Player::One, &world.snake)).chain(rival) {
+        let (head_position, queue_positions) = snake_state.interpolated(clock.alpha());
+        for (_, mut transform) in snake.iter_mut().filter(|(head, _)| head.0 == player) {
+            transform.translation = head_position;
+        }
+        // Queue members all look the same, so the order in which they are
+        // rendered doesn't matter.
+        let mut positions = queue_positions.into_iter();
+        for (entity, _, mut transform) in
+            queue.iter_mut().filter(|(_, member, _)| member.0 == player)
+        {
+            match positions.next() {
+                Some(position) => transform.translation = position,
+                // The queue has shrunk since the last frame.
+                None => commands.entity(entity).despawn(),
             }
         }
+        // The queue has grown since the last frame.
+        for position in positions {
+            Queue(player).spawn(
+                Transform::default().with_translation(position),
+                &mut commands,
+                &mut materials,
+                &mut meshes,
+            );
+        }
     }
 }
-/// Timeouts the extra bonus if it is on the screen.
-pub fn extra_bonus_timeout(
+
+/// Renders the bonuses of the game world, spawning and despawning their
+/// entities when needed.
+pub fn render_bonuses(
     mut commands: Commands,
-    bonus_query: Query<(Entity, &Handle<ColorMaterial>, &Bonus)>,
-    time: Res<Time>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    mut extra_bonus_timer: ResMut<ExtraBonusTimer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    world: Res<GameWorld>,
+    mut bonus_query: Query<(
+        Entity,
+        &BonusId,
+        &mut Transform,
+        &Handle<ColorMaterial>,
+        &Bonus,
+    )>,
 ) {
-    // We rather iter here, since we do not know whether an extra bonus is on the screen or
-    // not. However, only one extra bonus should be on the screen.
-    for (entity, material, bonus) in bonus_query.iter() {
-        match bonus {
-            Bonus::Normal => (),
-            Bonus::ExtraBonus => {
-                extra_bonus_timer.tick(time.delta());
-                let elapsed_secs = extra_bonus_timer.elapsed_secs();
-                // While the extra bonus is on the screen, we fade it out.
-                if elapsed_secs < TIME_FOR_BONUS {
-                    let new_alpha = 1f32 - elapsed_secs / TIME_FOR_BONUS;
-                    let mut color_mat = materials.get_mut(material).unwrap();
-                    color_mat.color = Color::Rgba {
-                        red: EXTRA_BONUS_RGB.0,
-                        green: EXTRA_BONUS_RGB.1,
-                        blue: EXTRA_BONUS_RGB.2,
-                        alpha: new_alpha,
-                    };
-                // If the time limit has been reached, we despawn the extra bonus.
-                } else {
-                    commands.entity(entity).despawn();
-                    extra_bonus_timer.reset();
+    for (entity, id, mut transform, material, bonus) in bonus_query.iter_mut() {
+        match world.bonuses.iter().find(|bonus| bonus.id == **id) {
+            Some(bonus_state) => {
+                transform.translation = bonus_state.position;
+                // While an extra bonus is on the screen, we fade it out along
+                // its own lifetime.
+                if *bonus != Bonus::Normal {
+                    let color_mat = materials.get_mut(material).unwrap();
+                    color_mat.color = bonus.get_color(bonus_state.time_left());
                 }
             }
+            // The bonus has been eaten or has timed out.
+            None => commands.entity(entity).despawn(),
+        }
+    }
+    for bonus in world.bonuses.iter() {
+        if !bonus_query.iter().any(|(_, id, ..)| **id == bonus.id) {
+            spawn_bonus(bonus, &mut commands, &mut materials, &mut meshes);
+        }
+    }
+}
+
+/// Renders a bar for each timed effect applying to the snake, in the bottom
+/// left corner, shrinking as the effect wears off.
+///
+/// The bars are spawned again each time the effects applying change.
+pub fn render_effects(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    world: Res<GameWorld>,
+    mut indicators: Query<(Entity, &EffectIndicator, &mut Style)>,
+) {
+    let effects: Vec<_> = world.snake.effects.iter().collect();
+    let is_outdated = indicators.iter().count() != effects.len()
+        || indicators.iter().any(|(_, indicator, _)| {
+            effects.get(indicator.index).map(|active| active.effect) != Some(indicator.effect)
+        });
+    if !is_outdated {
+        for (_, indicator, mut style) in indicators.iter_mut() {
+            let time_left = effects[indicator.index].time_left();
+            style.size.width = Val::Px(EFFECT_INDICATOR_WIDTH * time_left);
+        }
+        return;
+    }
+    for (entity, ..) in indicators.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (index, active) in effects.into_iter().enumerate() {
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(
+                        Val::Px(EFFECT_INDICATOR_WIDTH * active.time_left()),
+                        Val::Px(20.0),
+                    ),
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(10.0),
+                        bottom: Val::Px(10.0 + index as f32 * 25.0),
+                        ..default()
+                    },
+                    align_items: AlignItems::Center,
+                    overflow: Overflow::Hidden,
+                    ..default()
+                },
+                color: active.effect.color().into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle::from_section(
+                    active.effect.label(),
+                    TextStyle {
+                        font: asset_server.load(FONT_ASSET_NAME),
+                        font_size: 16.0,
+                        color: Color::BLACK,
+                    },
+                ));
+            })
+            .insert(EffectIndicator {
+                index,
+                effect: active.effect,
+            });
+    }
+}
+
+/// Renders the obstacles of the game world at their current position,
+/// interpolated between the last two ticks.
+pub fn render_obstacles(
+    world: Res<GameWorld>,
+    clock: Res<SimulationClock>,
+    mut obstacle_query: Query<(&ObstacleId, &mut Transform)>,
+) {
+    let shapes = world.interpolated_obstacle_shapes(clock.alpha());
+    for (id, mut transform) in obstacle_query.iter_mut() {
+        if let Some(shape) = shapes.get(**id) {
+            *transform = get_obstacle_transform(shape);
         }
     }
 }
@@ -107,16 +299,3 @@ pub fn enter_pause(keyboard_input: ResMut<Input<KeyCode>>, game_state: ResMut<St
         game_state,
     );
 }
-
-/// Handle a snake direction change on input.
-pub fn handle_snake_direction_input(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Snake>,
-) {
-    if let Some(new_direction) = get_direction_from_input(keyboard_input) {
-        let mut snake = query.single_mut();
-        if !snake.direction.unwrap().conflicts_with(new_direction) {
-            snake.direction = Some(new_direction);
-        }
-    }
-}
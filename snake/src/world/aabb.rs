@@ -0,0 +1,31 @@
+use bevy::math::{Vec2, Vec3};
+
+/// An axis aligned bounding box, used by the game world to detect collisions.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Aabb {
+    /// The center of the box.
+    pub center: Vec3,
+    /// The width and height of the box.
+    pub size: Vec2,
+}
+
+impl Aabb {
+    /// Creates a new box from its center and its size.
+    pub fn new(center: Vec3, size: Vec2) -> Self {
+        Self { center, size }
+    }
+
+    /// Returns whether the two boxes overlap.
+    ///
+    /// Boxes that only touch each others aren't considered as overlapping.
+    pub fn collides(&self, other: &Aabb) -> bool {
+        let self_min = self.center.truncate() - self.size / 2f32;
+        let self_max = self.center.truncate() + self.size / 2f32;
+        let other_min = other.center.truncate() - other.size / 2f32;
+        let other_max = other.center.truncate() + other.size / 2f32;
+        self_min.x < other_max.x
+            && self_max.x > other_min.x
+            && self_min.y < other_max.y
+            && self_max.y > other_min.y
+    }
+}
@@ -0,0 +1,243 @@
+use bevy::render::color::Color;
+
+/// An effect lasting for a while on the snake.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimedEffect {
+    /// The snake speed is multiplied by the given factor.
+    Speed(f32),
+    /// The snake goes through the walls and the obstacles.
+    Ghost,
+    /// The points rewarded by the bonuses are multiplied by the given factor.
+    ScoreMultiplier(u32),
+}
+
+/// What happens when an effect is applied while the same one already applies.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StackRule {
+    /// The effect lasts for the new duration, unless more time was left.
+    Refresh,
+    /// The new duration is added to the time left.
+    Extend,
+    /// The effect applies once more, up to the given number of times, each
+    /// one wearing off on its own.
+    Stack(usize),
+}
+
+impl TimedEffect {
+    /// Returns what happens when the effect is applied while it already applies.
+    pub fn stack_rule(&self) -> StackRule {
+        match self {
+            Self::Speed(_) => StackRule::Refresh,
+            Self::Ghost => StackRule::Extend,
+            Self::ScoreMultiplier(_) => StackRule::Stack(3),
+        }
+    }
+
+    /// Returns the short text describing the effect in the HUD.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Speed(factor) if *factor < 1f32 => format!("Slow x{}", factor),
+            Self::Speed(factor) => format!("Fast x{}", factor),
+            Self::Ghost => "Ghost".into(),
+            Self::ScoreMultiplier(factor) => format!("Score x{}", factor),
+        }
+    }
+
+    /// Returns the color of the effect in the HUD.
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Speed(factor) if *factor < 1f32 => Color::CYAN,
+            Self::Speed(_) => Color::ORANGE_RED,
+            Self::Ghost => Color::rgb(0.8, 0.8, 1f32),
+            Self::ScoreMultiplier(_) => Color::FUCHSIA,
+        }
+    }
+}
+
+/// A timed effect applying to the snake.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ActiveEffect {
+    /// The effect.
+    pub effect: TimedEffect,
+    /// The time left before the effect wears off, in seconds.
+    pub remaining: f32,
+    /// The whole time the effect lasts for, in seconds.
+    pub duration: f32,
+}
+
+impl ActiveEffect {
+    /// Returns the time left before the effect wears off, as a ratio.
+    pub fn time_left(&self) -> f32 {
+        self.remaining / self.duration
+    }
+}
+
+/// The timed effects applying to the snake at the same time.
+///
+/// The effects only wear off as the game world is stepped, so they are
+/// frozen while the game is paused.
+#[derive(Debug, Default, Clone)]
+pub struct StatusEffects {
+    active: Vec<ActiveEffect>,
+}
+
+impl StatusEffects {
+    /// Applies an effect for the given duration, following its [`StackRule`],
+    /// and returns whether it didn't apply already.
+    pub fn add(&mut self, effect: TimedEffect, duration: f32) -> bool {
+        let same_effects = self
+            .active
+            .iter()
+            .filter(|active| active.effect == effect)
+            .count();
+        let new_effect = ActiveEffect {
+            effect,
+            remaining: duration,
+            duration,
+        };
+        let existing = self
+            .active
+            .iter_mut()
+            .filter(|active| active.effect == effect)
+            .min_by(|active, other| active.remaining.total_cmp(&other.remaining));
+        match (effect.stack_rule(), existing) {
+            (_, None) => self.active.push(new_effect),
+            (StackRule::Refresh, Some(active)) => {
+                active.remaining = active.remaining.max(duration);
+                active.duration = active.duration.max(active.remaining);
+            }
+            (StackRule::Extend, Some(active)) => {
+                active.remaining += duration;
+                active.duration = active.remaining;
+            }
+            (StackRule::Stack(max), Some(_)) if same_effects < max => self.active.push(new_effect),
+            // The stack is full, the instance wearing off first lasts again.
+            (StackRule::Stack(_), Some(active)) => *active = new_effect,
+        }
+        same_effects == 0
+    }
+
+    /// Wears the effects off by the given elapsed time, and returns the ones
+    /// that stopped applying.
+    pub fn tick(&mut self, delta_seconds: f32) -> Vec<TimedEffect> {
+        for active in self.active.iter_mut() {
+            active.remaining -= delta_seconds;
+        }
+        let mut expired = Vec::new();
+        self.active.retain(|active| {
+            let is_active = 0f32 < active.remaining;
+            if !is_active {
+                expired.push(active.effect);
+            }
+            is_active
+        });
+        // An effect stacked several times only stops once its last instance wore off.
+        expired.retain(|effect| !self.active.iter().any(|active| active.effect == *effect));
+        expired.dedup();
+        expired
+    }
+
+    /// Returns the effects applying, in the order they were applied.
+    pub fn iter(&self) -> impl Iterator<Item = &ActiveEffect> {
+        self.active.iter()
+    }
+
+    /// Returns whether no effect applies.
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Returns the factor the snake speed is multiplied by.
+    pub fn speed_factor(&self) -> f32 {
+        self.active
+            .iter()
+            .filter_map(|active| match active.effect {
+                TimedEffect::Speed(factor) => Some(factor),
+                _ => None,
+            })
+            .product()
+    }
+
+    /// Returns whether the snake goes through the walls and the obstacles.
+    pub fn is_ghost(&self) -> bool {
+        self.active
+            .iter()
+            .any(|active| active.effect == TimedEffect::Ghost)
+    }
+
+    /// Returns the factor the points of the eaten bonuses are multiplied by.
+    pub fn score_multiplier(&self) -> u32 {
+        self.active
+            .iter()
+            .filter_map(|active| match active.effect {
+                TimedEffect::ScoreMultiplier(factor) => Some(factor),
+                _ => None,
+            })
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refreshed_effect_lasts_for_the_longest_time() {
+        let mut effects = StatusEffects::default();
+        assert!(effects.add(TimedEffect::Speed(1.5), 5f32));
+        effects.tick(2f32);
+        assert!(!effects.add(TimedEffect::Speed(1.5), 5f32));
+        assert!(!effects.add(TimedEffect::Speed(1.5), 1f32));
+        assert_eq!(effects.iter().count(), 1);
+        assert_eq!(effects.iter().next().unwrap().remaining, 5f32);
+        assert_eq!(effects.speed_factor(), 1.5);
+    }
+
+    #[test]
+    fn extended_effect_adds_up_the_durations() {
+        let mut effects = StatusEffects::default();
+        assert!(effects.add(TimedEffect::Ghost, 4f32));
+        effects.tick(1f32);
+        assert!(!effects.add(TimedEffect::Ghost, 4f32));
+        assert_eq!(effects.iter().count(), 1);
+        assert_eq!(effects.iter().next().unwrap().remaining, 7f32);
+        assert!(effects.tick(6f32).is_empty());
+        assert!(effects.is_ghost());
+        assert_eq!(effects.tick(1f32), vec![TimedEffect::Ghost]);
+        assert!(!effects.is_ghost());
+    }
+
+    #[test]
+    fn stacked_effect_applies_up_to_its_limit() {
+        let multiplier = TimedEffect::ScoreMultiplier(2);
+        let mut effects = StatusEffects::default();
+        assert!(effects.add(multiplier, 8f32));
+        effects.tick(1f32);
+        assert!(!effects.add(multiplier, 8f32));
+        effects.tick(1f32);
+        assert!(!effects.add(multiplier, 8f32));
+        assert_eq!(effects.score_multiplier(), 8);
+        // The stack is full, the instance wearing off first lasts again.
+        assert!(!effects.add(multiplier, 8f32));
+        assert_eq!(effects.score_multiplier(), 8);
+        let remaining: Vec<f32> = effects.iter().map(|active| active.remaining).collect();
+        assert_eq!(remaining, vec![8f32, 7f32, 8f32]);
+        // The effect only stops once its last instance wore off.
+        assert!(effects.tick(7f32).is_empty());
+        assert_eq!(effects.score_multiplier(), 4);
+        assert_eq!(effects.tick(1f32), vec![multiplier]);
+        assert!(effects.is_empty());
+        assert_eq!(effects.score_multiplier(), 1);
+    }
+
+    #[test]
+    fn different_effects_apply_together() {
+        let mut effects = StatusEffects::default();
+        assert!(effects.add(TimedEffect::Speed(1.5), 5f32));
+        assert!(effects.add(TimedEffect::Speed(0.5), 5f32));
+        assert!(effects.add(TimedEffect::Ghost, 4f32));
+        assert_eq!(effects.speed_factor(), 0.75);
+        assert_eq!(effects.tick(4f32), vec![TimedEffect::Ghost]);
+        assert_eq!(effects.iter().count(), 2);
+    }
+}
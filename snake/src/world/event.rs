@@ -0,0 +1,39 @@
+use super::effect::TimedEffect;
+use crate::components::prelude::Bonus;
+
+/// The event following a conflict of position between the snake and a collider.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum CollisionEvent {
+    /// The snake hit a border.
+    #[default]
+    Border,
+    /// The snake bit its own queue.
+    Queue,
+    /// The snake hit the other snake of a versus game, its head or its queue.
+    Rival,
+    /// The snake ate a bonus.
+    Bonus(Bonus),
+}
+
+/// The events produced by the game world on each step.
+///
+/// The renderer can listen to them in order to react to what happened in the game.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameEvent {
+    /// The snake collided with something.
+    Collision(CollisionEvent),
+    /// A bonus appeared on the playfield, or has been moved elsewhere.
+    BonusSpawned(Bonus),
+    /// A bonus disappeared because the player didn't eat it in time.
+    BonusExpired(Bonus),
+    /// A timed effect started applying to the snake.
+    EffectStarted(TimedEffect),
+    /// A timed effect stopped applying to the snake.
+    EffectExpired(TimedEffect),
+    /// The snake went through a portal.
+    Teleported,
+    /// The snake died, the game is over.
+    GameOver,
+    /// The snake filled the whole playfield, the player won.
+    BoardFull,
+}
@@ -0,0 +1,71 @@
+use bevy::math::{Vec2, Vec3};
+
+use crate::common::*;
+
+use super::aabb::Aabb;
+
+/// The playfield divided in `cols x rows` cells of the snake size, used by the
+/// grid movement mode.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Grid {
+    /// The number of columns.
+    pub cols: u32,
+    /// The number of rows.
+    pub rows: u32,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            cols: (SCREEN_WIDTH / SNAKE_SIZE) as u32,
+            rows: (SCREEN_HEIGHT / SNAKE_SIZE) as u32,
+        }
+    }
+}
+
+impl Grid {
+    /// Returns the center of the given cell.
+    pub fn cell_center(&self, col: u32, row: u32) -> Vec3 {
+        Vec3::new(
+            MIN_SCREEN_WIDTH + (col as f32 + 0.5) * SNAKE_SIZE,
+            MIN_SCREEN_HEIGHT + (row as f32 + 0.5) * SNAKE_SIZE,
+            0f32,
+        )
+    }
+
+    /// Returns the cell containing the given position, the positions out of
+    /// the playfield are clamped to the closest cell.
+    pub fn cell_of(&self, position: Vec3) -> (u32, u32) {
+        let col = ((position.x - MIN_SCREEN_WIDTH) / SNAKE_SIZE).floor();
+        let row = ((position.y - MIN_SCREEN_HEIGHT) / SNAKE_SIZE).floor();
+        (
+            col.clamp(0f32, (self.cols - 1) as f32) as u32,
+            row.clamp(0f32, (self.rows - 1) as f32) as u32,
+        )
+    }
+
+    /// Returns the center of the cell containing the given position.
+    pub fn snap(&self, position: Vec3) -> Vec3 {
+        let (col, row) = self.cell_of(position);
+        self.cell_center(col, row)
+    }
+
+    /// Grows the given box so that its edges lie on the cells edges, making it
+    /// cover every cell it was partially covering.
+    pub fn snap_aabb(&self, aabb: Aabb) -> Aabb {
+        let to_edge = |value: f32, origin: f32, round: fn(f32) -> f32| {
+            origin + round((value - origin) / SNAKE_SIZE) * SNAKE_SIZE
+        };
+        let min = aabb.center.truncate() - aabb.size / 2f32;
+        let max = aabb.center.truncate() + aabb.size / 2f32;
+        let min = Vec2::new(
+            to_edge(min.x, MIN_SCREEN_WIDTH, f32::floor),
+            to_edge(min.y, MIN_SCREEN_HEIGHT, f32::floor),
+        );
+        let max = Vec2::new(
+            to_edge(max.x, MIN_SCREEN_WIDTH, f32::ceil),
+            to_edge(max.y, MIN_SCREEN_HEIGHT, f32::ceil),
+        );
+        Aabb::new(((min + max) / 2f32).extend(aabb.center.z), max - min)
+    }
+}
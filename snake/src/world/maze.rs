@@ -0,0 +1,226 @@
+use bevy::math::Vec2;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::common::*;
+use crate::resources::prelude::Wall;
+
+use super::rng::GameRng;
+use super::snake::SnakeDirection;
+
+/// A maze dividing the playfield in rooms of [`MAZE_CELL_SIZE`], generated
+/// with a recursive backtracker.
+///
+/// The backtracker digs a spanning tree, so every room can be reached. Some
+/// more walls are then removed so the snake can loop around.
+#[derive(Debug, Clone)]
+pub struct Maze {
+    /// The number of rooms per row.
+    cols: usize,
+    /// The number of rooms per column.
+    rows: usize,
+    /// Whether each room is open toward its right neighbour.
+    open_right: Vec<bool>,
+    /// Whether each room is open toward its upper neighbour.
+    open_up: Vec<bool>,
+}
+
+impl Maze {
+    /// Generates a maze covering the screen.
+    pub fn generate(rng: &mut GameRng) -> Self {
+        let cols = (SCREEN_WIDTH / MAZE_CELL_SIZE) as usize;
+        let rows = (SCREEN_HEIGHT / MAZE_CELL_SIZE) as usize;
+        let mut maze = Self {
+            cols,
+            rows,
+            open_right: vec![false; cols * rows],
+            open_up: vec![false; cols * rows],
+        };
+        let mut visited = vec![false; cols * rows];
+        let start = (rng.gen_range(0..cols), rng.gen_range(0..rows));
+        visited[maze.index(start)] = true;
+        let mut stack = vec![start];
+        while let Some(&room) = stack.last() {
+            let unvisited: Vec<(usize, usize)> = maze
+                .neighbours(room)
+                .into_iter()
+                .filter(|neighbour| !visited[maze.index(*neighbour)])
+                .collect();
+            match unvisited.choose(rng) {
+                Some(&next) => {
+                    maze.open(room, next);
+                    visited[maze.index(next)] = true;
+                    stack.push(next);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+        // Braids the maze, a perfect maze has too many dead ends for a snake.
+        for col in 0..cols {
+            for row in 0..rows {
+                for neighbour in [(col + 1, row), (col, row + 1)] {
+                    if neighbour.0 < cols && neighbour.1 < rows && rng.gen_bool(MAZE_BRAIDING) {
+                        maze.open((col, row), neighbour);
+                    }
+                }
+            }
+        }
+        maze
+    }
+
+    /// Returns the index of the given room.
+    fn index(&self, (col, row): (usize, usize)) -> usize {
+        row * self.cols + col
+    }
+
+    /// Returns the rooms next to the given one.
+    fn neighbours(&self, (col, row): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut neighbours = Vec::new();
+        if 0 < col {
+            neighbours.push((col - 1, row));
+        }
+        if col + 1 < self.cols {
+            neighbours.push((col + 1, row));
+        }
+        if 0 < row {
+            neighbours.push((col, row - 1));
+        }
+        if row + 1 < self.rows {
+            neighbours.push((col, row + 1));
+        }
+        neighbours
+    }
+
+    /// Removes the wall between two neighbour rooms.
+    fn open(&mut self, room: (usize, usize), neighbour: (usize, usize)) {
+        let lowest = room.min(neighbour);
+        let index = self.index(lowest);
+        if room.1 == neighbour.1 {
+            self.open_right[index] = true;
+        } else {
+            self.open_up[index] = true;
+        }
+    }
+
+    /// Returns the directions the given room is open toward.
+    pub fn passages(&self, (col, row): (usize, usize)) -> Vec<SnakeDirection> {
+        let mut passages = Vec::new();
+        if self.open_right[self.index((col, row))] {
+            passages.push(SnakeDirection::Right);
+        }
+        if 0 < col && self.open_right[self.index((col - 1, row))] {
+            passages.push(SnakeDirection::Left);
+        }
+        if self.open_up[self.index((col, row))] {
+            passages.push(SnakeDirection::Up);
+        }
+        if 0 < row && self.open_up[self.index((col, row - 1))] {
+            passages.push(SnakeDirection::Down);
+        }
+        passages
+    }
+
+    /// Picks a random room, and returns the center of its free area.
+    pub fn random_room(&self, rng: &mut GameRng) -> ((usize, usize), Vec2) {
+        let room = (rng.gen_range(0..self.cols), rng.gen_range(0..self.rows));
+        let free_size = MAZE_CELL_SIZE - SNAKE_SIZE;
+        let center = self.corner(room) + Vec2::splat(free_size / 2f32);
+        (room, center)
+    }
+
+    /// Returns the lower left corner of the given room.
+    fn corner(&self, (col, row): (usize, usize)) -> Vec2 {
+        Vec2::new(
+            MIN_SCREEN_WIDTH + col as f32 * MAZE_CELL_SIZE,
+            MIN_SCREEN_HEIGHT + row as f32 * MAZE_CELL_SIZE,
+        )
+    }
+
+    /// Returns the walls of the maze, aligned on the cells of the grid mode.
+    ///
+    /// Each room owns the walls on its right and upper sides, as thick as a
+    /// cell, and the posts joining them on its upper right corner. The screen
+    /// edges close the maze.
+    pub fn walls(&self) -> Vec<Wall> {
+        let wall = |min: Vec2, max: Vec2| Wall {
+            center: (min + max) / 2f32,
+            size: max - min,
+        };
+        let mut walls = Vec::new();
+        for col in 0..self.cols {
+            for row in 0..self.rows {
+                let room = (col, row);
+                let index = self.index(room);
+                let max = self.corner(room) + Vec2::splat(MAZE_CELL_SIZE);
+                let min = self.corner(room);
+                let has_right_wall = col + 1 < self.cols && !self.open_right[index];
+                let has_upper_wall = row + 1 < self.rows && !self.open_up[index];
+                if has_right_wall {
+                    walls.push(wall(
+                        Vec2::new(max.x - SNAKE_SIZE, min.y),
+                        Vec2::new(max.x, max.y - SNAKE_SIZE),
+                    ));
+                }
+                if has_upper_wall {
+                    walls.push(wall(
+                        Vec2::new(min.x, max.y - SNAKE_SIZE),
+                        Vec2::new(max.x - SNAKE_SIZE, max.y),
+                    ));
+                }
+                let is_inner_corner = col + 1 < self.cols && row + 1 < self.rows;
+                let has_neighbour_wall = is_inner_corner
+                    && (!self.open_up[self.index((col + 1, row))]
+                        || !self.open_right[self.index((col, row + 1))]);
+                if is_inner_corner && (has_right_wall || has_upper_wall || has_neighbour_wall) {
+                    walls.push(wall(max - Vec2::splat(SNAKE_SIZE), max));
+                }
+            }
+        }
+        walls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the room next to the given one, toward the given direction.
+    fn next_room((col, row): (usize, usize), direction: SnakeDirection) -> (usize, usize) {
+        match direction {
+            SnakeDirection::Right => (col + 1, row),
+            SnakeDirection::Left => (col - 1, row),
+            SnakeDirection::Up => (col, row + 1),
+            SnakeDirection::Down => (col, row - 1),
+        }
+    }
+
+    #[test]
+    fn every_room_can_be_reached() {
+        for seed in 0..50 {
+            let maze = Maze::generate(&mut GameRng::new(Some(seed)));
+            let mut reached = vec![false; maze.cols * maze.rows];
+            reached[maze.index((0, 0))] = true;
+            let mut rooms = vec![(0, 0)];
+            while let Some(room) = rooms.pop() {
+                for direction in maze.passages(room) {
+                    let next = next_room(room, direction);
+                    assert!(next.0 < maze.cols && next.1 < maze.rows, "seed {}", seed);
+                    // A passage leads both ways.
+                    assert!(
+                        maze.passages(next)
+                            .iter()
+                            .any(|back| back.conflicts_with(direction)),
+                        "seed {}",
+                        seed
+                    );
+                    if !reached[maze.index(next)] {
+                        reached[maze.index(next)] = true;
+                        rooms.push(next);
+                    }
+                }
+            }
+            assert!(reached.iter().all(|reached| *reached), "seed {}", seed);
+        }
+    }
+}
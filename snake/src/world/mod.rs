@@ -0,0 +1,1060 @@
+pub mod aabb;
+pub mod effect;
+pub mod event;
+pub mod grid;
+pub mod maze;
+pub mod obstacle;
+pub mod placement;
+pub mod replay;
+pub mod rng;
+pub mod scoring;
+pub mod snake;
+pub mod stats;
+pub mod versus;
+
+use crate::common::*;
+use crate::components::prelude::{Bonus, BonusEffect};
+use crate::resources::prelude::{GameSettings, Level, MovementMode, PlayMode, WallRule};
+
+use aabb::Aabb;
+use bevy::math::{Vec2, Vec3};
+use event::{CollisionEvent, GameEvent};
+use grid::Grid;
+use obstacle::ObstacleShape;
+use placement::PlacementError;
+use rand::{seq::SliceRandom, Rng};
+use replay::Replay;
+use rng::GameRng;
+use scoring::ScoreBreakdown;
+use snake::{SnakeDirection, SnakeState};
+use stats::{DeathCause, GameStats};
+use versus::{Player, Rival, VersusOutcome};
+
+/// The input given to the game world on each step.
+#[derive(Debug, Default, Clone)]
+pub struct GameInput {
+    /// The turns requested by the player since the last step, in order.
+    pub turns: Vec<SnakeDirection>,
+    /// The turns requested by the second player of a versus game since the
+    /// last step, in order.
+    pub rival_turns: Vec<SnakeDirection>,
+}
+
+/// A bonus lying on the playfield.
+#[derive(Debug, Copy, Clone)]
+pub struct BonusState {
+    /// Unique identifier of the bonus within the game, used by the renderer
+    /// to match its entities.
+    pub id: u32,
+    /// The kind of bonus.
+    pub kind: Bonus,
+    /// Its position.
+    pub position: Vec3,
+    /// The time the bonus lies on the playfield before it expires, in
+    /// seconds, none if it never expires.
+    pub lifetime: Option<f32>,
+    /// The time elapsed since the bonus appeared, in seconds.
+    pub elapsed: f32,
+}
+
+impl BonusState {
+    /// Returns the time left before the bonus expires, as a ratio.
+    pub fn time_left(&self) -> f32 {
+        match self.lifetime {
+            Some(lifetime) => 1f32 - self.elapsed / lifetime,
+            None => 1f32,
+        }
+    }
+
+    /// Returns whether the bonus lied on the playfield for its whole lifetime.
+    fn is_expired(&self) -> bool {
+        matches!(self.lifetime, Some(lifetime) if lifetime <= self.elapsed)
+    }
+}
+
+/// The game world holds every rule of the game, without depending on any
+/// Bevy system.
+///
+/// It is advanced with [`GameWorld::step`], and the renderer only has to display
+/// its state.
+#[derive(Debug, Clone)]
+pub struct GameWorld {
+    /// The level the game is played on.
+    pub level: Level,
+    /// The settings the game is played with.
+    pub settings: GameSettings,
+    /// The cells of the playfield, used in grid mode.
+    pub grid: Grid,
+    /// The source of every random decision of the game.
+    pub rng: GameRng,
+    /// The borders hitboxes, aligned on the cells in grid mode.
+    pub borders: Vec<Aabb>,
+    /// The centers of the linked portals, on the cells in grid mode.
+    pub portals: Vec<(Vec3, Vec3)>,
+    /// The player.
+    pub snake: SnakeState,
+    /// The second player, in a versus game.
+    pub rival: Option<Rival>,
+    /// The bonuses currently on the playfield.
+    pub bonuses: Vec<BonusState>,
+    /// The score, raised by the points of the eaten bonuses, the combos and
+    /// the quickness of the player.
+    pub score: u32,
+    /// The detail of the score.
+    pub score_breakdown: ScoreBreakdown,
+    /// What happened during the game.
+    pub stats: GameStats,
+    /// Whether the game ended, either because the snake died or because it
+    /// filled the whole playfield.
+    pub over: bool,
+    /// How the versus game ended, once it did.
+    pub outcome: Option<VersusOutcome>,
+    /// The number of ticks simulated since the start of the game.
+    pub tick: u32,
+    /// The record of the game.
+    pub replay: Replay,
+    /// The identifier given to the next spawned bonus.
+    next_bonus_id: u32,
+    /// The center of the portal the head came out of, it can't go through it
+    /// again before leaving it.
+    portal_exit: Option<Vec3>,
+}
+
+impl GameWorld {
+    /// Creates a new game for the given level and settings, with the snake
+    /// at its initial position and a first bonus.
+    pub fn new(level: Level, settings: GameSettings) -> Self {
+        let grid = Grid::default();
+        let mut borders = level.get_borders();
+        let rng = GameRng::new(settings.seed);
+        let replay = Replay::new(rng.seed(), level.clone(), settings);
+        let mut snake_position = level.get_snake_initial_position();
+        let mut portals: Vec<(Vec3, Vec3)> = level
+            .portals
+            .iter()
+            .map(|pair| (pair.a.extend(0f32), pair.b.extend(0f32)))
+            .collect();
+        if let MovementMode::Grid = settings.movement_mode {
+            borders = borders
+                .into_iter()
+                .map(|border| grid.snap_aabb(border))
+                .collect();
+            snake_position = grid.snap(snake_position);
+            portals = portals
+                .into_iter()
+                .map(|(a, b)| (grid.snap(a), grid.snap(b)))
+                .collect();
+        }
+        let mut snake = SnakeState::new(snake_position, settings.movement_mode, level.edges);
+        snake.grow(settings.starting_length);
+        let mut world = Self {
+            level,
+            settings,
+            grid,
+            rng,
+            borders,
+            portals,
+            snake,
+            rival: None,
+            bonuses: Vec::new(),
+            score: 0,
+            score_breakdown: ScoreBreakdown::new(settings.difficulty.score_multiplier(&settings)),
+            stats: GameStats::default(),
+            over: false,
+            outcome: None,
+            tick: 0,
+            replay,
+            next_bonus_id: 0,
+            portal_exit: None,
+        };
+        if let PlayMode::Versus = settings.play_mode {
+            let mut rival_snake = SnakeState::new(
+                world.rival_spawn_position(),
+                settings.movement_mode,
+                world.level.edges,
+            );
+            rival_snake.grow(settings.starting_length);
+            world.rival = Some(Rival::new(
+                rival_snake,
+                settings.difficulty.score_multiplier(&settings),
+            ));
+        }
+        // A level without any free cell can't be won, the snake will hit something anyway.
+        let _ = world.spawn_bonus(Bonus::Normal);
+        world
+    }
+
+    /// Starts the game, with the snake going toward the given direction, and
+    /// returns whether it started.
+    ///
+    /// The snake can't start by going the opposite way of the level spawn direction.
+    /// In a versus game, the second snake starts toward the open space, away
+    /// from the path of the first one.
+    pub fn start(&mut self, direction: SnakeDirection) -> bool {
+        if let Some(spawn_direction) = self.level.spawn.direction {
+            if spawn_direction.conflicts_with(direction) {
+                return false;
+            }
+        }
+        let rival_direction = self
+            .rival
+            .as_ref()
+            .map(|rival| self.rival_start_direction(&rival.snake, direction));
+        if let (Some(rival), Some(rival_direction)) = (&mut self.rival, rival_direction) {
+            rival.snake.turn(rival_direction);
+        }
+        self.snake.turn(direction);
+        self.replay.first_direction = Some(direction);
+        true
+    }
+
+    /// Records that the player paused the game.
+    pub fn pause(&mut self) {
+        self.replay.record_pause(self.tick);
+    }
+
+    /// Advances the game by a single tick of [`SIMULATION_TIME_STEP`] with the given input,
+    /// and returns what happened during the step.
+    pub fn step(&mut self, input: GameInput) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.over {
+            return events;
+        }
+        self.replay.record(self.tick, &input);
+        self.tick += 1;
+        for turn in input.turns {
+            self.snake.queue_turn(turn);
+        }
+        self.snake.speed = self.speed();
+        if self.snake.direction.is_some() {
+            self.stats.duration += SIMULATION_TIME_STEP;
+            self.stats.distance += self.snake.speed * SIMULATION_TIME_STEP;
+        }
+        if self.snake.advance(SIMULATION_TIME_STEP) {
+            self.stats.turns += 1;
+        }
+        if Self::go_through_portals(&self.portals, &mut self.snake, &mut self.portal_exit) {
+            events.push(GameEvent::Teleported);
+        }
+        self.step_rival(input.rival_turns);
+        // Both snakes moved before their collisions are checked, so none of
+        // them is favored.
+        let rival_snake = self.rival.as_ref().map(|rival| &rival.snake);
+        let collision = self.check_collisions(&self.snake, rival_snake);
+        let rival_collision = rival_snake
+            .and_then(|rival_snake| self.check_collisions(rival_snake, Some(&self.snake)));
+        // The events tell what happened to the first player's snake only.
+        let mut is_dead = false;
+        if let Some(collision) = collision {
+            events.push(GameEvent::Collision(collision));
+            is_dead = self.handle_collision(Player::One, collision, &mut events);
+            if is_dead {
+                self.stats.death_cause = Some(self.death_cause(collision));
+            }
+        }
+        let is_rival_dead = rival_collision
+            .is_some_and(|collision| self.handle_collision(Player::Two, collision, &mut events));
+        // A snake filling the board on the same tick already ended the game.
+        if (is_dead || is_rival_dead) && !self.over {
+            self.over = true;
+            self.outcome = self
+                .rival
+                .as_ref()
+                .map(|rival| match (is_dead, is_rival_dead) {
+                    (false, _) => VersusOutcome::Winner(Player::One),
+                    (_, false) => VersusOutcome::Winner(Player::Two),
+                    // Both snakes died on the same tick, the longest one wins.
+                    _ => VersusOutcome::by_highest(self.snake.queue.len(), rival.snake.queue.len()),
+                });
+            events.push(GameEvent::GameOver);
+        }
+        if !self.over {
+            self.tick_bonuses(SIMULATION_TIME_STEP, &mut events);
+            for effect in self.snake.effects.tick(SIMULATION_TIME_STEP) {
+                events.push(GameEvent::EffectExpired(effect));
+            }
+            if let Some(rival) = &mut self.rival {
+                rival.snake.effects.tick(SIMULATION_TIME_STEP);
+            }
+        }
+        self.stats.max_length = self.stats.max_length.max(self.snake.queue.len());
+        self.stats.record(&events);
+        events
+    }
+
+    /// Returns the snake speed given the progress made and the speed effects,
+    /// in pixels per second.
+    pub fn speed(&self) -> f32 {
+        self.speed_of(&self.snake, self.score_breakdown.eaten)
+    }
+
+    /// Returns the speed of the given snake given the number of bonuses it ate
+    /// and its speed effects, in pixels per second.
+    fn speed_of(&self, snake: &SnakeState, bonuses_eaten: u32) -> f32 {
+        let time = self.tick as f32 * SIMULATION_TIME_STEP;
+        let speed = self.settings.speed.speed_at(bonuses_eaten, time);
+        (speed * snake.effects.speed_factor()).min(SNAKE_MAX_SPEED_FACTOR)
+    }
+
+    /// Moves the snake of the second player with its turns, in a versus game.
+    fn step_rival(&mut self, turns: Vec<SnakeDirection>) {
+        let Some(mut rival) = self.rival.take() else {
+            return;
+        };
+        for turn in turns {
+            rival.snake.queue_turn(turn);
+        }
+        rival.snake.speed = self.speed_of(&rival.snake, rival.score_breakdown.eaten);
+        rival.snake.advance(SIMULATION_TIME_STEP);
+        Self::go_through_portals(&self.portals, &mut rival.snake, &mut rival.portal_exit);
+        self.rival = Some(rival);
+    }
+
+    /// Returns the shapes of the obstacles at the given time since the start
+    /// of the game, in seconds.
+    pub fn obstacle_shapes_at(&self, time: f32) -> Vec<ObstacleShape> {
+        self.level
+            .obstacles
+            .iter()
+            .map(|obstacle| obstacle.shape_at(time))
+            .collect()
+    }
+
+    /// Returns the shapes of the obstacles, interpolated between the last two
+    /// ticks for the renderer.
+    pub fn interpolated_obstacle_shapes(&self, alpha: f32) -> Vec<ObstacleShape> {
+        let ticks = (self.tick as f32 - 1f32 + alpha).max(0f32);
+        self.obstacle_shapes_at(ticks * SIMULATION_TIME_STEP)
+    }
+
+    /// Returns the hitbox of the given snake's head.
+    fn head_hitbox(snake: &SnakeState) -> Aabb {
+        Aabb::new(snake.position, SNAKE_DIMENSIONS)
+    }
+
+    /// Moves the head of the snake to the paired portal if it entered one,
+    /// keeping its direction and its offset to the portal center, and returns
+    /// whether it did.
+    ///
+    /// The head can't go through the portal it came out of before leaving it.
+    fn go_through_portals(
+        portals: &[(Vec3, Vec3)],
+        snake: &mut SnakeState,
+        portal_exit: &mut Option<Vec3>,
+    ) -> bool {
+        let head = snake.position;
+        let is_inside = |portal: Vec3| {
+            (head - portal)
+                .truncate()
+                .abs()
+                .cmplt(Vec2::splat(PORTAL_SIZE / 2f32))
+                .all()
+        };
+        if let Some(exit) = *portal_exit {
+            if is_inside(exit) {
+                return false;
+            }
+            *portal_exit = None;
+        }
+        let destination = portals.iter().find_map(|(a, b)| {
+            if is_inside(*a) {
+                Some((*a, *b))
+            } else if is_inside(*b) {
+                Some((*b, *a))
+            } else {
+                None
+            }
+        });
+        match destination {
+            Some((entry, exit)) => {
+                snake.teleport(exit + head - entry);
+                *portal_exit = Some(exit);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether the given snake has collided anything, a bonus, a border,
+    /// an obstacle at its current position, the solid screen edges, its queue,
+    /// or the other snake of a versus game.
+    ///
+    /// The borders and the obstacles are ignored while the snake is a ghost.
+    fn check_collisions(
+        &self,
+        snake: &SnakeState,
+        other: Option<&SnakeState>,
+    ) -> Option<CollisionEvent> {
+        let head = Self::head_hitbox(snake);
+        let hits_walls = || {
+            self.borders.iter().any(|border| head.collides(border))
+                || self
+                    .obstacle_shapes_at(self.tick as f32 * SIMULATION_TIME_STEP)
+                    .iter()
+                    .any(|obstacle| obstacle.collides(&head))
+        };
+        if !snake.is_on_screen() || (!snake.effects.is_ghost() && hits_walls()) {
+            return Some(CollisionEvent::Border);
+        }
+        if snake.bites_itself() {
+            return Some(CollisionEvent::Queue);
+        }
+        if other.is_some_and(|other| snake.hits(other)) {
+            return Some(CollisionEvent::Rival);
+        }
+        self.eaten_bonus(snake)
+            .map(|bonus| CollisionEvent::Bonus(bonus.kind))
+    }
+
+    /// Returns the bonus the head of the given snake lies on, if any.
+    fn eaten_bonus(&self, snake: &SnakeState) -> Option<&BonusState> {
+        let head = Self::head_hitbox(snake);
+        self.bonuses
+            .iter()
+            .find(|bonus| head.collides(&Aabb::new(bonus.position, Vec2::splat(BONUS_DIAMETER))))
+    }
+
+    /// Every collision event handling of the given player's snake, returns
+    /// whether the snake died.
+    fn handle_collision(
+        &mut self,
+        player: Player,
+        collision: CollisionEvent,
+        events: &mut Vec<GameEvent>,
+    ) -> bool {
+        match collision {
+            CollisionEvent::Bonus(bonus) => {
+                self.eat_bonus(player, bonus, events);
+                false
+            }
+            // If a border is collided while the walls are forgiving, the snake
+            // turns around.
+            CollisionEvent::Border if self.settings.wall_rule == WallRule::Bounce => {
+                self.player_mut(player).0.bounce();
+                false
+            }
+            // If a border, the queue or the other snake is collided, the snake dies.
+            CollisionEvent::Border | CollisionEvent::Queue | CollisionEvent::Rival => true,
+        }
+    }
+
+    /// Increases the length of the queue of the given player's snake, and
+    /// others subsequent actions of eating the bonus.
+    ///
+    /// Nothing happens if the other snake already ate the bonus on this step.
+    fn eat_bonus(&mut self, player: Player, bonus: Bonus, events: &mut Vec<GameEvent>) {
+        let Some((eaten_id, bonus_age)) = self
+            .eaten_bonus(self.player_snake(player))
+            .map(|eaten| (eaten.id, eaten.elapsed))
+        else {
+            return;
+        };
+        let tick = self.tick;
+        let spec = bonus.spec();
+        let (snake, score_breakdown, score) = self.player_mut(player);
+        snake.grow(spec.points);
+        score_breakdown.eat(
+            spec.points * snake.effects.score_multiplier(),
+            tick,
+            bonus_age,
+        );
+        *score = score_breakdown.total();
+        match spec.effect {
+            BonusEffect::None => {}
+            BonusEffect::Shrink(members) => snake.shrink(members),
+            BonusEffect::Timed(effect, duration) => {
+                if snake.effects.add(effect, duration) && player == Player::One {
+                    events.push(GameEvent::EffectStarted(effect));
+                }
+            }
+        }
+        match bonus {
+            // The normal bonus is moved elsewhere, and we roll the dice
+            // for an extra bonus if there is room for one more.
+            // If there is no room left for the normal bonus, the player won,
+            // or the player with the highest score in a versus game.
+            Bonus::Normal => match self.random_bonus_position() {
+                Ok(new_position) => {
+                    for normal_bonus in self
+                        .bonuses
+                        .iter_mut()
+                        .filter(|bonus| matches!(bonus.kind, Bonus::Normal))
+                    {
+                        normal_bonus.position = new_position;
+                        normal_bonus.elapsed = 0f32;
+                    }
+                    events.push(GameEvent::BonusSpawned(Bonus::Normal));
+                    if self.extra_bonus_count() < self.settings.max_extra_bonuses
+                        && self.rng.gen_bool(self.settings.extra_bonus_chance)
+                    {
+                        let kind = self.random_extra_bonus();
+                        if self.spawn_bonus(kind).is_ok() {
+                            events.push(GameEvent::BonusSpawned(kind));
+                        }
+                    }
+                }
+                Err(PlacementError::BoardFull) => {
+                    self.bonuses.clear();
+                    self.over = true;
+                    self.outcome = self
+                        .rival
+                        .as_ref()
+                        .map(|rival| VersusOutcome::by_highest(self.score, rival.score));
+                    events.push(GameEvent::BoardFull);
+                }
+            },
+            // The extra bonus is removed.
+            _ => self.bonuses.retain(|bonus| bonus.id != eaten_id),
+        }
+    }
+
+    /// Returns the snake of the given player.
+    fn player_snake(&self, player: Player) -> &SnakeState {
+        match (player, &self.rival) {
+            (Player::Two, Some(rival)) => &rival.snake,
+            _ => &self.snake,
+        }
+    }
+
+    /// Returns the snake, the score breakdown and the score of the given player.
+    fn player_mut(&mut self, player: Player) -> (&mut SnakeState, &mut ScoreBreakdown, &mut u32) {
+        match (player, &mut self.rival) {
+            (Player::Two, Some(rival)) => (
+                &mut rival.snake,
+                &mut rival.score_breakdown,
+                &mut rival.score,
+            ),
+            _ => (&mut self.snake, &mut self.score_breakdown, &mut self.score),
+        }
+    }
+
+    /// Returns what the snake died of, given the deadly collision.
+    fn death_cause(&self, collision: CollisionEvent) -> DeathCause {
+        let head = Self::head_hitbox(&self.snake);
+        match collision {
+            CollisionEvent::Queue => DeathCause::Queue,
+            CollisionEvent::Rival => DeathCause::Rival,
+            _ if !self.snake.is_on_screen() => DeathCause::ScreenEdge,
+            _ if self.borders.iter().any(|border| head.collides(border)) => DeathCause::Wall,
+            _ => DeathCause::Obstacle,
+        }
+    }
+
+    /// Ages the bonuses, and removes the ones that lied on the playfield for
+    /// their whole lifetime.
+    fn tick_bonuses(&mut self, delta_seconds: f32, events: &mut Vec<GameEvent>) {
+        for bonus in self.bonuses.iter_mut() {
+            bonus.elapsed += delta_seconds;
+        }
+        for bonus in self.bonuses.iter().filter(|bonus| bonus.is_expired()) {
+            events.push(GameEvent::BonusExpired(bonus.kind));
+        }
+        self.bonuses.retain(|bonus| !bonus.is_expired());
+    }
+
+    /// Picks the kind of a new extra bonus, according to their weights.
+    fn random_extra_bonus(&mut self) -> Bonus {
+        Bonus::EXTRA
+            .choose_weighted(&mut self.rng, |bonus| bonus.spec().weight)
+            .copied()
+            .unwrap_or(Bonus::ExtraBonus)
+    }
+
+    /// Returns the number of extra bonuses on the playfield.
+    fn extra_bonus_count(&self) -> u32 {
+        self.bonuses
+            .iter()
+            .filter(|bonus| bonus.kind != Bonus::Normal)
+            .count() as u32
+    }
+
+    /// Computes a random position for a bonus, on the center of a free cell in grid mode.
+    ///
+    /// A few random positions are tried first, then every cell of the playfield
+    /// is checked, so the placement fails only when no free cell is left once
+    /// the snake finished growing.
+    ///
+    /// The positions too close to the snake head are avoided, unless they are
+    /// the only free ones.
+    fn random_bonus_position(&mut self) -> Result<Vec3, PlacementError> {
+        let occupied_space = self.occupied_space();
+        let is_free = |world: &Self, position: Vec3| {
+            let bonus = world.bonus_hitbox(position);
+            !occupied_space
+                .iter()
+                .any(|occupied| bonus.collides(occupied))
+        };
+        for _ in 0..BONUS_PLACEMENT_ATTEMPTS {
+            let mut position = Vec3::new(
+                self.rng.gen_range(MIN_SCREEN_WIDTH..MAX_SCREEN_WIDTH),
+                self.rng.gen_range(MIN_SCREEN_HEIGHT..MAX_SCREEN_HEIGHT),
+                0f32,
+            );
+            if let MovementMode::Grid = self.settings.movement_mode {
+                position = self.grid.snap(position);
+            }
+            if self.is_far_from_head(position) && is_free(self, position) {
+                return Ok(position);
+            }
+        }
+        let grid = self.grid;
+        let free_cells: Vec<Vec3> = (0..grid.cols)
+            .flat_map(|col| (0..grid.rows).map(move |row| grid.cell_center(col, row)))
+            .filter(|position| is_free(self, *position))
+            .collect();
+        let pending_growth = self.snake.pending_growth()
+            + self
+                .rival
+                .as_ref()
+                .map(|rival| rival.snake.pending_growth())
+                .unwrap_or_default();
+        if free_cells.len() <= pending_growth {
+            return Err(PlacementError::BoardFull);
+        }
+        let far_cells: Vec<Vec3> = free_cells
+            .iter()
+            .copied()
+            .filter(|position| self.is_far_from_head(*position))
+            .collect();
+        far_cells
+            .choose(&mut self.rng)
+            .or_else(|| free_cells.choose(&mut self.rng))
+            .copied()
+            .ok_or(PlacementError::BoardFull)
+    }
+
+    /// Returns the hitbox of a bonus lying at the given position, which covers
+    /// its whole cell in grid mode.
+    fn bonus_hitbox(&self, position: Vec3) -> Aabb {
+        match self.settings.movement_mode {
+            MovementMode::Continuous => Aabb::new(position, Vec2::splat(BONUS_DIAMETER)),
+            MovementMode::Grid => Aabb::new(position, SNAKE_DIMENSIONS),
+        }
+    }
+
+    /// Returns whether the given position is far enough from the snake heads to spawn a bonus.
+    fn is_far_from_head(&self, position: Vec3) -> bool {
+        std::iter::once(&self.snake)
+            .chain(self.rival.iter().map(|rival| &rival.snake))
+            .all(|snake| self.settings.bonus_min_head_distance <= position.distance(snake.position))
+    }
+
+    /// Returns where the second snake of a versus game spawns : opposite to
+    /// the first one across the center of the screen, or on the closest cell
+    /// that is free, far enough from the first snake, and open toward at least
+    /// one direction.
+    fn rival_spawn_position(&self) -> Vec3 {
+        let mut mirror = -self.snake.position;
+        if let MovementMode::Grid = self.settings.movement_mode {
+            mirror = self.grid.snap(mirror);
+        }
+        let occupied_space = self.occupied_space();
+        let is_open = |position: Vec3| {
+            let head = Aabb::new(position, SNAKE_DIMENSIONS);
+            RIVAL_MIN_SPAWN_DISTANCE <= position.distance(self.snake.position)
+                && !occupied_space
+                    .iter()
+                    .any(|occupied| head.collides(occupied))
+                && SnakeDirection::ALL.into_iter().any(|direction| {
+                    Self::clear_run(position, direction, &occupied_space) == RIVAL_CLEAR_RUN
+                })
+        };
+        if is_open(mirror) {
+            return mirror;
+        }
+        let grid = self.grid;
+        let mut cells: Vec<Vec3> = (0..grid.cols)
+            .flat_map(|col| (0..grid.rows).map(move |row| grid.cell_center(col, row)))
+            .collect();
+        cells.sort_by(|a, b| a.distance(mirror).total_cmp(&b.distance(mirror)));
+        cells
+            .into_iter()
+            .find(|cell| is_open(*cell))
+            .unwrap_or(mirror)
+    }
+
+    /// Returns the direction the second snake of a versus game starts toward,
+    /// given the one of the first snake : the one with the most open space
+    /// ahead, the opposite of the first snake direction on a tie.
+    ///
+    /// The walls and the path the first snake is about to take close the way,
+    /// so the second snake follows the passages of a maze instead of running
+    /// into its walls.
+    fn rival_start_direction(
+        &self,
+        rival: &SnakeState,
+        direction: SnakeDirection,
+    ) -> SnakeDirection {
+        let first_path = (0..=2 * RIVAL_CLEAR_RUN).map(|step| {
+            let ahead = direction.into_translation() * SNAKE_SIZE * step as f32;
+            Aabb::new(self.snake.position + ahead, SNAKE_DIMENSIONS)
+        });
+        let blocking_space: Vec<Aabb> = self
+            .occupied_space()
+            .into_iter()
+            .chain(first_path)
+            .collect();
+        let preferred = direction.get_conflictual_direction();
+        SnakeDirection::ALL
+            .into_iter()
+            .fold(preferred, |best, candidate| {
+                let run = Self::clear_run(rival.position, candidate, &blocking_space);
+                if Self::clear_run(rival.position, best, &blocking_space) < run {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Returns the number of snake lengths, up to [`RIVAL_CLEAR_RUN`], a head
+    /// lying at the given position can move toward the given direction
+    /// without hitting the given space nor leaving the screen.
+    fn clear_run(position: Vec3, direction: SnakeDirection, blocking_space: &[Aabb]) -> usize {
+        (1..=RIVAL_CLEAR_RUN)
+            .take_while(|step| {
+                let ahead = position + direction.into_translation() * SNAKE_SIZE * *step as f32;
+                let head = Aabb::new(ahead, SNAKE_DIMENSIONS);
+                ahead.x.abs() <= MAX_SCREEN_WIDTH
+                    && ahead.y.abs() <= MAX_SCREEN_HEIGHT
+                    && !blocking_space
+                        .iter()
+                        .any(|blocking| head.collides(blocking))
+            })
+            .count()
+    }
+
+    /// Returns the hitboxes of everything lying on the playfield : the borders,
+    /// the area swept by the obstacles, the portals, the snakes and the bonuses.
+    fn occupied_space(&self) -> Vec<Aabb> {
+        let portals = self
+            .portals
+            .iter()
+            .flat_map(|(a, b)| [*a, *b])
+            .map(|portal| Aabb::new(portal, Vec2::splat(PORTAL_SIZE)));
+        let obstacles = self
+            .level
+            .obstacles
+            .iter()
+            .map(|obstacle| obstacle.swept_area());
+        let snake = std::iter::once(&self.snake)
+            .chain(self.rival.iter().map(|rival| &rival.snake))
+            .flat_map(|snake| std::iter::once(&snake.position).chain(snake.queue.iter()))
+            .map(|position| Aabb::new(*position, SNAKE_DIMENSIONS));
+        let bonuses = self
+            .bonuses
+            .iter()
+            .map(|bonus| Aabb::new(bonus.position, Vec2::splat(BONUS_DIAMETER)));
+        self.borders
+            .iter()
+            .copied()
+            .chain(obstacles)
+            .chain(portals)
+            .chain(snake)
+            .chain(bonuses)
+            .collect()
+    }
+
+    /// Spawns a bonus of the given kind at a random position.
+    fn spawn_bonus(&mut self, kind: Bonus) -> Result<(), PlacementError> {
+        let position = self.random_bonus_position()?;
+        let lifetime = kind
+            .spec()
+            .lifetime
+            .map(|ratio| ratio * self.settings.extra_bonus_lifetime);
+        self.bonuses.push(BonusState {
+            id: self.next_bonus_id,
+            kind,
+            position,
+            lifetime,
+            elapsed: 0f32,
+        });
+        self.next_bonus_id += 1;
+        Ok(())
+    }
+}
+
+pub mod prelude {
+    pub use super::aabb::Aabb;
+    pub use super::effect::*;
+    pub use super::event::*;
+    pub use super::grid::Grid;
+    pub use super::maze::Maze;
+    pub use super::obstacle::ObstacleShape;
+    pub use super::placement::PlacementError;
+    pub use super::replay::*;
+    pub use super::rng::GameRng;
+    pub use super::scoring::ScoreBreakdown;
+    pub use super::snake::*;
+    pub use super::stats::*;
+    pub use super::versus::*;
+    pub use super::{BonusState, GameInput, GameWorld};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use replay::ReplayPlayer;
+    use std::path::Path;
+
+    /// The seed of the test games.
+    const TEST_SEED: u64 = 1;
+
+    /// Returns the levels shipped with the game.
+    fn levels() -> Vec<Level> {
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        BUILTIN_LEVELS
+            .iter()
+            .map(|path| {
+                ron::from_str(&std::fs::read_to_string(assets.join(path)).unwrap()).unwrap()
+            })
+            .collect()
+    }
+
+    /// Returns the level shipped with the game with the given name.
+    fn level(name: &str) -> Level {
+        levels()
+            .into_iter()
+            .find(|level| level.name == name)
+            .unwrap()
+    }
+
+    /// Returns the settings of the test games.
+    fn test_settings(movement_mode: MovementMode, play_mode: PlayMode) -> GameSettings {
+        GameSettings {
+            movement_mode,
+            play_mode,
+            seed: Some(TEST_SEED),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a new single player game on the given level, with the snake
+    /// going right.
+    fn test_world(level: Level, movement_mode: MovementMode) -> GameWorld {
+        let mut world = GameWorld::new(level, test_settings(movement_mode, PlayMode::Solo));
+        world.start(SnakeDirection::Right);
+        world
+    }
+
+    /// Returns the hitbox spanning between the given corners.
+    fn aabb_between(min: Vec2, max: Vec2) -> Aabb {
+        Aabb::new(((min + max) / 2f32).extend(0f32), max - min)
+    }
+
+    /// Steps the world without any input until it produces the given event,
+    /// and returns the events of that step.
+    fn step_until(world: &mut GameWorld, event: GameEvent) -> Vec<GameEvent> {
+        for _ in 0..600 {
+            let events = world.step(GameInput::default());
+            if events.contains(&event) {
+                return events;
+            }
+        }
+        panic!("{:?} never happened", event);
+    }
+
+    #[test]
+    fn eating_a_bonus_grows_the_snake_and_moves_the_bonus() {
+        let mut world = test_world(level("Screen"), MovementMode::Continuous);
+        let bonus_position = world.snake.position + Vec3::new(3f32 * SNAKE_SIZE, 0f32, 0f32);
+        world.bonuses[0].position = bonus_position;
+        let events = step_until(
+            &mut world,
+            GameEvent::Collision(CollisionEvent::Bonus(Bonus::Normal)),
+        );
+        assert!(events.contains(&GameEvent::BonusSpawned(Bonus::Normal)));
+        assert_ne!(world.bonuses[0].position, bonus_position);
+        assert_eq!(world.snake.queue.len(), 1);
+        assert_eq!(world.stats.bonuses_eaten.get(Bonus::Normal), 1);
+        assert_eq!(world.score_breakdown.bonuses, 1);
+        assert_eq!(world.score, world.score_breakdown.total());
+        assert!(!world.over);
+    }
+
+    #[test]
+    fn hitting_a_wall_ends_the_game() {
+        for movement_mode in [MovementMode::Continuous, MovementMode::Grid] {
+            // The snake spawns on the left of the wall splitting the screen.
+            let mut world = test_world(level("Horizontal"), movement_mode);
+            step_until(&mut world, GameEvent::GameOver);
+            assert!(world.over);
+            assert!(world.snake.position.x < 0f32);
+            assert_eq!(world.stats.death_cause, Some(DeathCause::Wall));
+            assert!(world.step(GameInput::default()).is_empty());
+        }
+    }
+
+    #[test]
+    fn same_seed_places_the_same_bonuses() {
+        let bonus_positions = || {
+            let mut world = test_world(level("Cross"), MovementMode::Continuous);
+            (0..10)
+                .map(|_| world.random_bonus_position().unwrap())
+                .collect::<Vec<Vec3>>()
+        };
+        assert_eq!(bonus_positions(), bonus_positions());
+    }
+
+    #[test]
+    fn replaying_a_game_gives_the_same_events() {
+        use SnakeDirection::*;
+        for level in levels() {
+            for movement_mode in [MovementMode::Continuous, MovementMode::Grid] {
+                let mut world = test_world(level.clone(), movement_mode);
+                let mut played = Vec::new();
+                while !world.over && world.tick < 600 {
+                    // The player turns every third of a second, toward each
+                    // direction in turn.
+                    let turns = match world.tick % 20 {
+                        0 => vec![[Up, Left, Down, Right][(world.tick / 20) as usize % 4]],
+                        _ => Vec::new(),
+                    };
+                    played.push(world.step(GameInput {
+                        turns,
+                        ..Default::default()
+                    }));
+                }
+                let replay = world.replay.clone();
+                let mut replayed = GameWorld::new(replay.level.clone(), replay.game_settings());
+                replayed.start(replay.first_direction.unwrap());
+                let mut player = ReplayPlayer::new(replay);
+                let replayed_events: Vec<Vec<GameEvent>> = played
+                    .iter()
+                    .map(|_| replayed.step(player.input_at(replayed.tick)))
+                    .collect();
+                let case = format!("{} {:?}", level.name, movement_mode);
+                assert_eq!(replayed_events, played, "{}", case);
+                assert_eq!(replayed.score, world.score, "{}", case);
+                assert_eq!(replayed.over, world.over, "{}", case);
+            }
+        }
+    }
+
+    #[test]
+    fn bonus_spawns_away_from_the_occupied_space() {
+        for level in levels() {
+            for movement_mode in [MovementMode::Continuous, MovementMode::Grid] {
+                let mut world = test_world(level.clone(), movement_mode);
+                world.snake.grow(10);
+                for _ in 0..50 {
+                    world.step(GameInput::default());
+                }
+                let occupied_space = world.occupied_space();
+                for _ in 0..50 {
+                    let position = world.random_bonus_position().unwrap();
+                    let bonus = Aabb::new(position, Vec2::splat(BONUS_DIAMETER));
+                    assert!(!occupied_space
+                        .iter()
+                        .any(|occupied| bonus.collides(occupied)));
+                    assert!(BONUS_MIN_HEAD_DISTANCE <= position.distance(world.snake.position));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filling_the_board_wins_the_game() {
+        // Only two cells are left free: the snake lies on the first one, and
+        // the bonus on the second one.
+        let mut world = test_world(level("Screen"), MovementMode::Grid);
+        let min = world.snake.position.truncate() - SNAKE_DIMENSIONS / 2f32;
+        let max = min + Vec2::new(2f32 * SNAKE_SIZE, SNAKE_SIZE);
+        let screen_min = Vec2::new(MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT);
+        let screen_max = Vec2::new(MAX_SCREEN_WIDTH, MAX_SCREEN_HEIGHT);
+        world.borders = vec![
+            aabb_between(screen_min, Vec2::new(min.x, screen_max.y)),
+            aabb_between(Vec2::new(max.x, screen_min.y), screen_max),
+            aabb_between(Vec2::new(min.x, screen_min.y), Vec2::new(max.x, min.y)),
+            aabb_between(Vec2::new(min.x, max.y), Vec2::new(max.x, screen_max.y)),
+        ];
+        world.bonuses[0].position = world.snake.position + Vec3::new(SNAKE_SIZE, 0f32, 0f32);
+        let events = step_until(&mut world, GameEvent::BoardFull);
+        assert!(!events.contains(&GameEvent::GameOver));
+        assert!(world.over);
+        assert!(world.bonuses.is_empty());
+        assert_eq!(world.stats.death_cause, None);
+    }
+
+    #[test]
+    fn rival_scores_the_bonuses_it_eats() {
+        let mut world = GameWorld::new(
+            level("Cross"),
+            test_settings(MovementMode::Continuous, PlayMode::Versus),
+        );
+        assert!(world.start(SnakeDirection::Right));
+        let rival = &world.rival.as_ref().unwrap().snake;
+        let bonus_position =
+            rival.position + rival.direction.unwrap().into_translation() * 3f32 * SNAKE_SIZE;
+        world.bonuses[0].position = bonus_position;
+        for _ in 0..20 {
+            world.step(GameInput::default());
+        }
+        let rival = world.rival.as_ref().unwrap();
+        assert_eq!(rival.score_breakdown.bonuses, 1);
+        assert_eq!(rival.snake.queue.len(), 1);
+        assert_eq!(world.score, 0);
+        assert_ne!(world.bonuses[0].position, bonus_position);
+    }
+
+    #[test]
+    fn versus_snakes_start_clear_of_each_other() {
+        let mazes = (0..20).map(|seed| Level::generate(Some(seed)));
+        for level in levels().into_iter().chain(mazes) {
+            for movement_mode in [MovementMode::Continuous, MovementMode::Grid] {
+                for direction in SnakeDirection::ALL {
+                    let settings = test_settings(movement_mode, PlayMode::Versus);
+                    let mut world = GameWorld::new(level.clone(), settings);
+                    if !world.start(direction) {
+                        continue;
+                    }
+                    let case = format!("{} {:?} {:?}", level.name, movement_mode, direction);
+                    // The first snake may run into a wall on its own, but
+                    // neither snake can hit the other one, nor can the second
+                    // one hit anything.
+                    for _ in 0..20 {
+                        let events = world.step(GameInput::default());
+                        assert!(
+                            !events.contains(&GameEvent::Collision(CollisionEvent::Rival)),
+                            "{}",
+                            case
+                        );
+                        if world.over {
+                            assert_eq!(
+                                world.outcome,
+                                Some(VersusOutcome::Winner(Player::Two)),
+                                "{}",
+                                case
+                            );
+                            break;
+                        }
+                        let rival = &world.rival.as_ref().unwrap().snake;
+                        let collision = world.check_collisions(rival, Some(&world.snake));
+                        assert!(
+                            !matches!(
+                                collision,
+                                Some(
+                                    CollisionEvent::Border
+                                        | CollisionEvent::Queue
+                                        | CollisionEvent::Rival
+                                )
+                            ),
+                            "{}",
+                            case
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generated_mazes_share_their_high_scores() {
+        let mazes = [Level::generate(Some(1)), Level::generate(Some(2))];
+        assert_ne!(mazes[0].name, mazes[1].name);
+        for maze in &mazes {
+            assert_eq!(maze.high_scores_name(), GENERATED_LEVELS_HIGH_SCORES_NAME);
+        }
+        assert_eq!(level("Cross").high_scores_name(), "Cross");
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::math::{Mat2, Vec2, Vec3};
+use std::f32::consts::TAU;
+
+use crate::resources::prelude::{Motion, Obstacle};
+
+use super::aabb::Aabb;
+
+/// The shape of an obstacle at a given time : a box that can be rotated.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct ObstacleShape {
+    /// The center of the box.
+    pub center: Vec3,
+    /// The width and height of the box, before its rotation.
+    pub size: Vec2,
+    /// The counterclockwise rotation of the box, in radians.
+    pub angle: f32,
+}
+
+impl ObstacleShape {
+    /// Returns whether the shape overlaps the given box.
+    ///
+    /// The boxes are separated if their projections on one of their axes are,
+    /// shapes that only touch the box aren't considered as overlapping.
+    pub fn collides(&self, aabb: &Aabb) -> bool {
+        let rotation = Mat2::from_angle(self.angle);
+        let (axis_u, axis_v) = (rotation.x_axis, rotation.y_axis);
+        let distance = self.center.truncate() - aabb.center.truncate();
+        [Vec2::X, Vec2::Y, axis_u, axis_v].into_iter().all(|axis| {
+            let aabb_radius = (aabb.size / 2f32).dot(axis.abs());
+            let shape_radius = self.size.x / 2f32 * axis.dot(axis_u).abs()
+                + self.size.y / 2f32 * axis.dot(axis_v).abs();
+            distance.dot(axis).abs() < aabb_radius + shape_radius
+        })
+    }
+
+    /// Returns the smallest axis aligned box containing the shape.
+    pub fn bounding_box(&self) -> Aabb {
+        let rotation = Mat2::from_angle(self.angle);
+        let half_extent = (rotation.x_axis * self.size.x / 2f32).abs()
+            + (rotation.y_axis * self.size.y / 2f32).abs();
+        Aabb::new(self.center, half_extent * 2f32)
+    }
+}
+
+impl Obstacle {
+    /// Returns the shape of the obstacle after the given time since the start
+    /// of the game, in seconds.
+    pub fn shape_at(&self, time: f32) -> ObstacleShape {
+        let center = self.wall.center;
+        let shape = ObstacleShape {
+            center: center.extend(0f32),
+            size: self.wall.size,
+            angle: 0f32,
+        };
+        match self.motion {
+            Motion::Patrol { offset, period } => ObstacleShape {
+                center: (center + offset * back_and_forth(time, period)).extend(0f32),
+                ..shape
+            },
+            Motion::Rotate { pivot, period } => {
+                let angle = if period == 0f32 {
+                    0f32
+                } else {
+                    TAU * time / period
+                };
+                let rotated = pivot + Mat2::from_angle(angle) * (center - pivot);
+                ObstacleShape {
+                    center: rotated.extend(0f32),
+                    angle,
+                    ..shape
+                }
+            }
+            Motion::Pulse { scale, period } => ObstacleShape {
+                size: self.wall.size * (1f32 + (scale - 1f32) * back_and_forth(time, period)),
+                ..shape
+            },
+        }
+    }
+
+    /// Returns a box containing every position the obstacle goes through.
+    pub fn swept_area(&self) -> Aabb {
+        let start = self.shape_at(0f32).bounding_box();
+        match self.motion {
+            Motion::Patrol { offset, .. } => {
+                let end = Aabb::new(start.center + offset.extend(0f32), start.size);
+                let min = (start.center.truncate() - start.size / 2f32)
+                    .min(end.center.truncate() - end.size / 2f32);
+                let max = (start.center.truncate() + start.size / 2f32)
+                    .max(end.center.truncate() + end.size / 2f32);
+                Aabb::new(((min + max) / 2f32).extend(0f32), max - min)
+            }
+            Motion::Rotate { pivot, .. } => {
+                // Every point of the obstacle stays within the circle reaching its furthest corner.
+                let radius = self.wall.center.distance(pivot) + self.wall.size.length() / 2f32;
+                Aabb::new(pivot.extend(0f32), Vec2::splat(radius * 2f32))
+            }
+            Motion::Pulse { scale, .. } => {
+                Aabb::new(start.center, self.wall.size * scale.max(1f32))
+            }
+        }
+    }
+}
+
+/// Returns a ratio going smoothly from 0 to 1 and back to 0 during a period.
+fn back_and_forth(time: f32, period: f32) -> f32 {
+    if period == 0f32 {
+        return 0f32;
+    }
+    (1f32 - (TAU * time / period).cos()) / 2f32
+}
@@ -0,0 +1,16 @@
+use std::fmt;
+
+/// The error that can happen while placing something on the playfield.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PlacementError {
+    /// There isn't any free cell left on the playfield.
+    BoardFull,
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BoardFull => write!(f, "There isn't any free cell left on the board"),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io};
+
+use crate::resources::prelude::{GameSettings, Level};
+
+use super::{snake::SnakeDirection, GameInput};
+
+/// An input recorded during a game.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum ReplayInput {
+    /// The player requested a turn.
+    Turn(SnakeDirection),
+    /// The second player of a versus game requested a turn.
+    RivalTurn(SnakeDirection),
+    /// The player paused the game.
+    ///
+    /// Pauses don't affect the simulation, they are only kept so the reviewers
+    /// know when the player took a break.
+    Pause,
+}
+
+/// The error that can happen while saving or loading a replay.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The replay file couldn't be read or written.
+    Io(io::Error),
+    /// The replay file content isn't valid.
+    Format(ron::Error),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Replay file couldn't be accessed : {}", error),
+            Self::Format(error) => write!(f, "Replay file is invalid : {}", error),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::Error> for ReplayError {
+    fn from(error: ron::Error) -> Self {
+        Self::Format(error)
+    }
+}
+
+/// The record of a game, allowing to simulate it again.
+///
+/// Since the game world is deterministic, the seed, the level, the settings
+/// and the inputs are enough to replay a game. The random numbers being drawn
+/// with ChaCha8, a replay plays the same on every platform. The inputs are kept
+/// along with the tick they happened at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// The seed of the game.
+    pub seed: u64,
+    /// The level the game has been played on, kept whole so the replay still
+    /// works if its file changes.
+    pub level: Level,
+    /// The settings the game has been played with.
+    pub settings: GameSettings,
+    /// The direction that started the game.
+    pub first_direction: Option<SnakeDirection>,
+    /// The inputs of the game, along with their tick.
+    pub inputs: Vec<(u32, ReplayInput)>,
+}
+
+impl Replay {
+    /// Creates an empty replay for a game.
+    pub fn new(seed: u64, level: Level, settings: GameSettings) -> Self {
+        Self {
+            seed,
+            level,
+            settings,
+            first_direction: None,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Records the input given at the given tick.
+    pub fn record(&mut self, tick: u32, input: &GameInput) {
+        self.inputs.extend(
+            input
+                .turns
+                .iter()
+                .map(|turn| (tick, ReplayInput::Turn(*turn))),
+        );
+        self.inputs.extend(
+            input
+                .rival_turns
+                .iter()
+                .map(|turn| (tick, ReplayInput::RivalTurn(*turn))),
+        );
+    }
+
+    /// Records a pause at the given tick.
+    pub fn record_pause(&mut self, tick: u32) {
+        self.inputs.push((tick, ReplayInput::Pause));
+    }
+
+    /// Returns the settings to create the replayed game with.
+    pub fn game_settings(&self) -> GameSettings {
+        GameSettings {
+            seed: Some(self.seed),
+            ..self.settings
+        }
+    }
+
+    /// Saves the replay at the given path.
+    pub fn save(&self, path: &str) -> Result<(), ReplayError> {
+        fs::write(path, ron::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Loads the replay saved at the given path.
+    pub fn load(path: &str) -> Result<Self, ReplayError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Feeds the game world with the inputs of a replay.
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    /// The replay being played.
+    replay: Replay,
+    /// The index of the next input to play.
+    cursor: usize,
+}
+
+impl ReplayPlayer {
+    /// Creates a player starting at the beginning of the replay.
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, cursor: 0 }
+    }
+
+    /// Returns the replay being played.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Returns the input that has been given at the given tick.
+    ///
+    /// The ticks have to be requested in order.
+    pub fn input_at(&mut self, tick: u32) -> GameInput {
+        let mut input = GameInput::default();
+        while let Some((input_tick, replay_input)) = self.replay.inputs.get(self.cursor) {
+            if tick < *input_tick {
+                break;
+            }
+            match replay_input {
+                ReplayInput::Turn(turn) => input.turns.push(*turn),
+                ReplayInput::RivalTurn(turn) => input.rival_turns.push(*turn),
+                ReplayInput::Pause => {}
+            }
+            self.cursor += 1;
+        }
+        input
+    }
+}
@@ -0,0 +1,74 @@
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generator of a game.
+///
+/// Every random decision of the game world is drawn from it, so two games
+/// played with the same seed and the same inputs are identical.
+///
+/// The generator is ChaCha8, whose output is specified, so a seed gives the
+/// same game on every platform and with every version of the dependencies.
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    /// The seed the generator has been created with.
+    seed: u64,
+    /// The generator itself.
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    /// Creates a generator from the given seed, or from a random one if none is given.
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the seed of the generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_draws_the_same_numbers() {
+        let draws = |seed| {
+            let mut rng = GameRng::new(Some(seed));
+            (0..10).map(|_| rng.next_u64()).collect::<Vec<u64>>()
+        };
+        assert_eq!(draws(42), draws(42));
+        assert_ne!(draws(42), draws(43));
+        assert_eq!(GameRng::new(Some(42)).seed(), 42);
+    }
+
+    #[test]
+    fn seed_draws_the_numbers_of_chacha8() {
+        // A different value means the seeds shared by the players give other games.
+        let mut rng = GameRng::new(Some(42));
+        assert_eq!(rng.next_u64(), 12578764544318200737);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::common::*;
+
+/// The points scored during a game, detailed by what rewarded them.
+///
+/// The score doesn't depend on the snake length : eating bonuses in quick
+/// succession builds a combo, and reaching a bonus quickly after it appeared
+/// rewards more points.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ScoreBreakdown {
+    /// The points of the eaten bonuses, multiplied by the score power-ups.
+    pub bonuses: u32,
+    /// The number of bonuses eaten, including the ones worth no point.
+    pub eaten: u32,
+    /// The points rewarded by the combos.
+    pub combos: u32,
+    /// The points rewarded for reaching the bonuses quickly.
+    pub quickness: u32,
+    /// The factor the points are multiplied by, given by the difficulty.
+    pub multiplier: f32,
+    /// The number of bonuses eaten in the current combo.
+    pub streak: u32,
+    /// The longest combo of the game.
+    pub best_streak: u32,
+    /// The tick the last bonus was eaten on.
+    last_meal_tick: Option<u32>,
+}
+
+impl ScoreBreakdown {
+    /// Creates an empty score, multiplied by the given factor.
+    pub fn new(multiplier: f32) -> Self {
+        Self {
+            multiplier,
+            ..Default::default()
+        }
+    }
+
+    /// Returns whether a bonus eaten on the given tick continues the combo.
+    fn is_combo(&self, tick: u32) -> bool {
+        self.last_meal_tick.is_some_and(|last_tick| {
+            (tick - last_tick) as f32 * SIMULATION_TIME_STEP <= COMBO_WINDOW
+        })
+    }
+
+    /// Returns the number of bonuses eaten in the combo still going on at the
+    /// given tick, zero once the combo is broken.
+    pub fn active_streak(&self, tick: u32) -> u32 {
+        if self.is_combo(tick) {
+            self.streak
+        } else {
+            0
+        }
+    }
+
+    /// Scores a bonus eaten on the given tick, worth the given points, which
+    /// appeared the given number of seconds ago.
+    ///
+    /// A bonus worth no point, like the shrinking one, neither counts in the
+    /// combo nor rewards its quickness.
+    pub fn eat(&mut self, points: u32, tick: u32, bonus_age: f32) {
+        self.eaten += 1;
+        if points == 0 {
+            return;
+        }
+        self.streak = if self.is_combo(tick) {
+            self.streak + 1
+        } else {
+            1
+        };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_meal_tick = Some(tick);
+        self.bonuses += points;
+        self.combos += self.streak - 1;
+        if bonus_age < QUICK_BONUS_TIME {
+            let quickness = 1f32 - bonus_age / QUICK_BONUS_TIME;
+            self.quickness += (quickness * QUICK_BONUS_POINTS as f32).ceil() as u32;
+        }
+    }
+
+    /// Returns the points scored before the difficulty multiplier.
+    pub fn raw_total(&self) -> u32 {
+        self.bonuses + self.combos + self.quickness
+    }
+
+    /// Returns the score of the game.
+    pub fn total(&self) -> u32 {
+        (self.raw_total() as f32 * self.multiplier).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bonus_worth_no_point_is_not_scored() {
+        let mut breakdown = ScoreBreakdown::new(1f32);
+        breakdown.eat(1, 0, QUICK_BONUS_TIME);
+        breakdown.eat(0, 1, 0f32);
+        assert_eq!(breakdown.streak, 1);
+        assert_eq!(breakdown.combos, 0);
+        assert_eq!(breakdown.quickness, 0);
+        assert_eq!(breakdown.total(), 1);
+        assert_eq!(breakdown.eaten, 2);
+    }
+
+    #[test]
+    fn bonuses_eaten_in_quick_succession_build_a_combo() {
+        let mut breakdown = ScoreBreakdown::new(1f32);
+        breakdown.eat(1, 0, QUICK_BONUS_TIME);
+        breakdown.eat(1, 60, QUICK_BONUS_TIME);
+        breakdown.eat(1, 120, QUICK_BONUS_TIME);
+        assert_eq!(breakdown.streak, 3);
+        assert_eq!(breakdown.active_streak(120), 3);
+        assert_eq!(breakdown.combos, 3);
+        // The combo breaks once no bonus was eaten for too long.
+        assert_eq!(breakdown.active_streak(400), 0);
+        breakdown.eat(1, 400, QUICK_BONUS_TIME);
+        assert_eq!(breakdown.streak, 1);
+        assert_eq!(breakdown.best_streak, 3);
+        assert_eq!(breakdown.combos, 3);
+        assert_eq!(breakdown.bonuses, 4);
+        assert_eq!(breakdown.total(), 7);
+    }
+
+    #[test]
+    fn quickly_reached_bonus_rewards_more_points() {
+        let mut breakdown = ScoreBreakdown::new(1f32);
+        breakdown.eat(1, 0, 0f32);
+        assert_eq!(breakdown.quickness, QUICK_BONUS_POINTS);
+        breakdown.eat(1, 600, QUICK_BONUS_TIME / 2f32);
+        assert_eq!(breakdown.quickness, QUICK_BONUS_POINTS + 2);
+        breakdown.eat(1, 1200, QUICK_BONUS_TIME);
+        assert_eq!(breakdown.quickness, QUICK_BONUS_POINTS + 2);
+        assert_eq!(breakdown.raw_total(), 3 + QUICK_BONUS_POINTS + 2);
+    }
+
+    #[test]
+    fn total_is_multiplied_by_the_difficulty() {
+        let mut breakdown = ScoreBreakdown::new(1.5);
+        breakdown.eat(3, 0, QUICK_BONUS_TIME);
+        assert_eq!(breakdown.raw_total(), 3);
+        assert_eq!(breakdown.total(), 5);
+        breakdown.eat(1, 60, QUICK_BONUS_TIME);
+        assert_eq!(breakdown.raw_total(), 5);
+        assert_eq!(breakdown.total(), 8);
+    }
+}
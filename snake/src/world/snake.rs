@@ -0,0 +1,556 @@
+use bevy::math::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::common::*;
+use crate::resources::prelude::{EdgeRule, MovementMode};
+
+use super::aabb::Aabb;
+use super::effect::StatusEffects;
+
+/// The snake direction in a 2D plan
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum SnakeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl SnakeDirection {
+    /// Every direction.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    /// Get the conflictual direction.
+    ///
+    /// ie, going upward is impossible for the snake if he is already
+    /// going down.
+    pub fn get_conflictual_direction(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Returns whether the current position conflicts with another.
+    pub fn conflicts_with(&self, other: Self) -> bool {
+        self.get_conflictual_direction() == other
+    }
+
+    /// Returns the direction closest to the given translation.
+    fn from_translation(translation: Vec3) -> Self {
+        if translation.y.abs() < translation.x.abs() {
+            if 0f32 < translation.x {
+                Self::Right
+            } else {
+                Self::Left
+            }
+        } else if 0f32 < translation.y {
+            Self::Up
+        } else {
+            Self::Down
+        }
+    }
+
+    /// Returns the current direction as a translatable vec.
+    pub fn into_translation(self) -> Vec3 {
+        match self {
+            Self::Up => Vec3::new(0., 1., 0.),
+            Self::Down => Vec3::new(0., -1., 0.),
+            Self::Right => Vec3::new(1., 0., 0.),
+            Self::Left => Vec3::new(-1., 0., 0.),
+        }
+    }
+}
+
+/// The state of the snake within the game world : its head and its queue.
+///
+/// The queue members follow the path of the head, spaced by the snake size
+/// along it.
+#[derive(Debug, Default, Clone)]
+pub struct SnakeState {
+    /// How the snake moves.
+    pub movement_mode: MovementMode,
+    /// What happens when the snake goes through the screen edges.
+    pub edges: EdgeRule,
+    /// The snake speed, in pixels per second.
+    pub speed: f32,
+    /// The snake direction, none until the player picks one.
+    pub direction: Option<SnakeDirection>,
+    /// The position of the head.
+    pub position: Vec3,
+    /// The position of the head before its last move.
+    pub last_position: Vec3,
+    /// The queue members, from the closest to the head to the tail.
+    pub queue: Vec<Vec3>,
+    /// The timed effects applying to the snake.
+    pub effects: StatusEffects,
+    /// The turns requested by the player and not applied yet, from the oldest
+    /// to the newest.
+    pub turns: VecDeque<SnakeDirection>,
+    /// The distance travelled since the last movement step.
+    travel: f32,
+    /// The total distance travelled by the head.
+    odometer: f32,
+    /// The positions the head went through along with the odometer at that
+    /// time, from the newest to the oldest.
+    path: VecDeque<(f32, Vec3)>,
+    /// The distance along the path between the head and each queue member.
+    queue_distances: Vec<f32>,
+    /// The queue members positions before the last move.
+    last_queue: Vec<Vec3>,
+    /// The number of queue members waiting on the tail, the head's path
+    /// being too short yet for them to unroll.
+    pending_growth: usize,
+}
+
+impl SnakeState {
+    /// Creates a new snake without any queue at the given position.
+    pub fn new(position: Vec3, movement_mode: MovementMode, edges: EdgeRule) -> Self {
+        Self {
+            movement_mode,
+            edges,
+            speed: SNAKE_SPEED_FACTOR,
+            position,
+            last_position: position,
+            path: VecDeque::from([(0f32, position)]),
+            ..Default::default()
+        }
+    }
+
+    /// Changes the snake direction, unless the new one conflicts with the current one.
+    pub fn turn(&mut self, new_direction: SnakeDirection) -> bool {
+        match self.direction {
+            Some(direction) if direction.conflicts_with(new_direction) => false,
+            _ => {
+                self.direction = Some(new_direction);
+                true
+            }
+        }
+    }
+
+    /// Queues a turn requested by the player, it will be applied on a later
+    /// movement step.
+    ///
+    /// The turn is ignored if the buffer is full, or if it is useless or
+    /// impossible given the direction the snake will have once the already
+    /// queued turns are applied.
+    pub fn queue_turn(&mut self, new_direction: SnakeDirection) {
+        let upcoming_direction = self.turns.back().copied().or(self.direction);
+        match upcoming_direction {
+            Some(direction)
+                if direction == new_direction || direction.conflicts_with(new_direction) => {}
+            _ if INPUT_BUFFER_SIZE <= self.turns.len() => {}
+            _ => self.turns.push_back(new_direction),
+        }
+    }
+
+    /// Applies the oldest queued turn, if any, and returns whether the snake turned.
+    fn apply_next_turn(&mut self) -> bool {
+        match self.turns.pop_front() {
+            Some(new_direction) => self.turn(new_direction),
+            None => false,
+        }
+    }
+
+    /// Advances the snake for the given elapsed time.
+    ///
+    /// A queued turn is applied on each movement step : in grid mode, a step
+    /// is a whole cell the snake moves by, once it travelled the size of a
+    /// cell. In continuous mode, the snake has to travel its own size between
+    /// two turns so it doesn't turn back into itself.
+    ///
+    /// Returns whether the snake turned.
+    pub fn advance(&mut self, delta_seconds: f32) -> bool {
+        if self.direction.is_none() {
+            return false;
+        }
+        let distance = self.speed * delta_seconds;
+        match self.movement_mode {
+            MovementMode::Continuous => {
+                let turned = SNAKE_SIZE <= self.travel && self.apply_next_turn();
+                if turned {
+                    self.travel = 0f32;
+                }
+                self.travel += distance;
+                self.move_by(distance);
+                turned
+            }
+            MovementMode::Grid => {
+                self.travel += distance;
+                if SNAKE_SIZE <= self.travel {
+                    self.travel -= SNAKE_SIZE;
+                    let turned = self.apply_next_turn();
+                    self.move_by(SNAKE_SIZE);
+                    turned
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Moves the head by the given distance toward its direction, and the
+    /// queue along the head's path.
+    fn move_by(&mut self, distance: f32) {
+        let Some(direction) = self.direction else {
+            return;
+        };
+        let mut new_translation = self.position + direction.into_translation() * distance;
+        let is_out_of_screen = MAX_SCREEN_WIDTH < f32::abs(new_translation.x)
+            || MAX_SCREEN_HEIGHT < f32::abs(new_translation.y);
+        if let (EdgeRule::Bounce, true) = (self.edges, is_out_of_screen) {
+            self.reverse();
+            return;
+        }
+        self.last_position = self.position;
+        if let EdgeRule::Wrap | EdgeRule::Mirror = self.edges {
+            // Upper or lower component translation when there is no border
+            if MAX_SCREEN_WIDTH < f32::abs(new_translation.x) {
+                new_translation.x -= SCREEN_WIDTH * new_translation.x.signum();
+                if let EdgeRule::Mirror = self.edges {
+                    new_translation.y = -new_translation.y;
+                }
+            }
+            // Upper or lower component translation when there is no border
+            if MAX_SCREEN_HEIGHT < f32::abs(new_translation.y) {
+                new_translation.y -= SCREEN_HEIGHT * new_translation.y.signum();
+            }
+        }
+        self.position = new_translation;
+        self.odometer += distance;
+        self.path.push_front((self.odometer, self.position));
+        self.last_queue = self.queue.clone();
+        self.place_queue();
+    }
+
+    /// Places each queue member on the head's path, spaced by [`QUEUE_SPACING`].
+    ///
+    /// The members the path is too short for wait on its oldest position, and
+    /// the points of the path that are behind the tail are forgotten.
+    fn place_queue(&mut self) {
+        let mut newer = 0;
+        let mut pending_growth = 0;
+        for (i, member) in self.queue.iter_mut().enumerate() {
+            let target = self.odometer - (i + 1) as f32 * QUEUE_SPACING;
+            while newer + 1 < self.path.len() && target < self.path[newer + 1].0 {
+                newer += 1;
+            }
+            let (newer_odometer, newer_position) = self.path[newer];
+            let (position, odometer) = match self.path.get(newer + 1) {
+                Some((older_odometer, older_position)) => {
+                    let step = newer_odometer - older_odometer;
+                    let ratio = (newer_odometer - target) / step;
+                    // The head went through the screen edges between those two points.
+                    if step < newer_position.distance(*older_position) {
+                        if ratio < 0.5 {
+                            (newer_position, newer_odometer)
+                        } else {
+                            (*older_position, *older_odometer)
+                        }
+                    } else {
+                        (newer_position.lerp(*older_position, ratio), target)
+                    }
+                }
+                None => {
+                    pending_growth += 1;
+                    (newer_position, newer_odometer)
+                }
+            };
+            *member = position;
+            self.queue_distances[i] = self.odometer - odometer;
+        }
+        self.pending_growth = pending_growth;
+        self.path.truncate(newer + 2);
+    }
+
+    /// Cancels the last move of the head, which hit something, and turns the
+    /// snake around.
+    pub fn bounce(&mut self) {
+        if 1 < self.path.len() {
+            self.path.pop_front();
+        }
+        if let Some((odometer, position)) = self.path.front() {
+            self.odometer = *odometer;
+            self.position = *position;
+        }
+        self.place_queue();
+        self.reverse();
+    }
+
+    /// Turns the snake around : the tail becomes the head, and the snake goes
+    /// back along its own path, as when it bounces on the screen edges.
+    ///
+    /// The members waiting on the tail are moved to the new tail, so they
+    /// still unroll behind the snake.
+    fn reverse(&mut self) {
+        let Some(direction) = self.direction else {
+            return;
+        };
+        let pending_growth = self.pending_growth;
+        let kept_members = self.queue.len() - pending_growth;
+        self.queue.truncate(kept_members);
+        self.queue_distances.truncate(kept_members);
+        let tail = self.queue.last().copied().unwrap_or(self.position);
+        let tail_odometer =
+            self.odometer - self.queue_distances.last().copied().unwrap_or_default();
+        // The new direction goes from the tail toward the path it came from.
+        let behind_tail = self
+            .path
+            .iter()
+            .find(|(odometer, position)| *odometer < tail_odometer && *position != tail)
+            .map(|(_, position)| *position - tail);
+        self.direction = Some(match behind_tail {
+            Some(way) if way.length() <= QUEUE_SPACING => SnakeDirection::from_translation(way),
+            _ => direction.get_conflictual_direction(),
+        });
+        // The path is walked the other way, starting from the tail.
+        let reversed_path = self
+            .path
+            .iter()
+            .rev()
+            .filter(|(odometer, _)| tail_odometer < *odometer)
+            .map(|(odometer, position)| (self.odometer - (odometer - tail_odometer), *position));
+        self.path = std::iter::once((self.odometer, tail))
+            .chain(reversed_path)
+            .collect();
+        self.position = tail;
+        self.last_position = tail;
+        self.turns.clear();
+        self.travel = 0f32;
+        self.place_queue();
+        self.last_queue = self.queue.clone();
+        self.grow(pending_growth as u32);
+    }
+
+    /// Moves the head to the given position without moving through the space
+    /// between, as when it goes through a portal.
+    ///
+    /// The path keeps the jump, so the queue members follow the head through
+    /// it the same way they go through the screen edges.
+    pub fn teleport(&mut self, position: Vec3) {
+        self.position = position;
+        if let Some((_, newest)) = self.path.front_mut() {
+            *newest = position;
+        }
+        self.place_queue();
+    }
+
+    /// Returns whether the head is within the screen, which it always is
+    /// unless the edges are solid.
+    pub fn is_on_screen(&self) -> bool {
+        f32::abs(self.position.x) <= MAX_SCREEN_WIDTH
+            && f32::abs(self.position.y) <= MAX_SCREEN_HEIGHT
+    }
+
+    /// Returns whether the head overlaps a member of its own queue.
+    ///
+    /// The members closer to the head than [`NECK_LENGTH`] along its path are
+    /// the neck, which always touches the head and thus can't be bitten, and
+    /// a member has to overlap the head by [`BITE_TOLERANCE`] to be bitten.
+    pub fn bites_itself(&self) -> bool {
+        let head = Aabb::new(self.position, SNAKE_DIMENSIONS - 2f32 * BITE_TOLERANCE);
+        self.queue
+            .iter()
+            .zip(self.queue_distances.iter())
+            .filter(|(_, distance)| NECK_LENGTH < **distance)
+            .any(|(member, _)| head.collides(&Aabb::new(*member, SNAKE_DIMENSIONS)))
+    }
+
+    /// Returns whether the head overlaps the head or a queue member of the
+    /// other snake, by [`BITE_TOLERANCE`] at least.
+    pub fn hits(&self, other: &SnakeState) -> bool {
+        let head = Aabb::new(self.position, SNAKE_DIMENSIONS - 2f32 * BITE_TOLERANCE);
+        std::iter::once(&other.position)
+            .chain(other.queue.iter())
+            .any(|member| head.collides(&Aabb::new(*member, SNAKE_DIMENSIONS)))
+    }
+
+    /// Returns the positions of the head and of the queue members, interpolated
+    /// between the previous move and the current one.
+    ///
+    /// The members that jumped further than a single move through the screen
+    /// edges, or that just appeared, aren't interpolated.
+    ///
+    /// In grid mode, the snake is always rendered on its cells.
+    pub fn interpolated(&self, alpha: f32) -> (Vec3, Vec<Vec3>) {
+        if let MovementMode::Grid = self.movement_mode {
+            return (self.position, self.queue.clone());
+        }
+        let max_move = self.speed * SIMULATION_TIME_STEP;
+        let interpolate = |from: Vec3, to: Vec3| {
+            if from.distance(to) <= max_move {
+                from.lerp(to, alpha)
+            } else {
+                to
+            }
+        };
+        let head = interpolate(self.last_position, self.position);
+        let queue = self
+            .queue
+            .iter()
+            .enumerate()
+            .map(|(i, member)| match self.last_queue.get(i) {
+                Some(former_position) => interpolate(*former_position, *member),
+                None => *member,
+            })
+            .collect();
+        (head, queue)
+    }
+
+    /// Returns the number of queue members that are waiting on the tail, and
+    /// will need room once they unroll.
+    pub fn pending_growth(&self) -> usize {
+        self.pending_growth
+    }
+
+    /// Decreases the queue length by the given number of members, starting
+    /// from the tail.
+    pub fn shrink(&mut self, members: u32) {
+        let length = self.queue.len().saturating_sub(members as usize);
+        // The members waiting on the tail are the first to go.
+        self.pending_growth = self
+            .pending_growth
+            .saturating_sub(self.queue.len() - length);
+        self.queue.truncate(length);
+        self.queue_distances.truncate(length);
+        self.last_queue.truncate(length);
+    }
+
+    /// Increases the queue length by the given number of members.
+    ///
+    /// The new members appear on the tail, and unroll as the snake moves.
+    pub fn grow(&mut self, members: u32) {
+        let tail = self.queue.last().copied().unwrap_or(self.position);
+        let tail_distance = self.queue_distances.last().copied().unwrap_or_default();
+        for _ in 0..members {
+            self.queue.push(tail);
+            self.queue_distances.push(tail_distance);
+        }
+        self.pending_growth += members as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a snake on a grid cell with the given number of members waiting
+    /// to unroll, going right.
+    fn grid_snake(members: u32) -> SnakeState {
+        let mut snake = SnakeState::new(
+            Vec3::new(5f32, 5f32, 0f32),
+            MovementMode::Grid,
+            EdgeRule::default(),
+        );
+        snake.grow(members);
+        snake.turn(SnakeDirection::Right);
+        snake
+    }
+
+    /// Moves the snake by a single cell toward the given direction.
+    fn move_once(snake: &mut SnakeState, direction: SnakeDirection) {
+        snake.queue_turn(direction);
+        let position = snake.position;
+        while snake.position == position {
+            snake.advance(SIMULATION_TIME_STEP);
+        }
+    }
+
+    /// Returns the cell of the first row the snake spawned on, at the given abscissa.
+    fn cell(x: f32) -> Vec3 {
+        Vec3::new(x, 5f32, 0f32)
+    }
+
+    #[test]
+    fn snake_bites_itself_once_it_turns_back_into_its_queue() {
+        let mut snake = grid_snake(5);
+        // The members waiting on the head can't be bitten.
+        assert!(!snake.bites_itself());
+        for direction in [
+            SnakeDirection::Right,
+            SnakeDirection::Right,
+            SnakeDirection::Right,
+            SnakeDirection::Right,
+            SnakeDirection::Up,
+            SnakeDirection::Left,
+        ] {
+            move_once(&mut snake, direction);
+            assert!(!snake.bites_itself());
+        }
+        move_once(&mut snake, SnakeDirection::Down);
+        assert!(snake.bites_itself());
+    }
+
+    #[test]
+    fn pending_growth_counts_the_members_waiting_on_the_tail() {
+        let mut snake = grid_snake(3);
+        assert_eq!(snake.pending_growth(), 3);
+        move_once(&mut snake, SnakeDirection::Right);
+        assert_eq!(snake.pending_growth(), 2);
+        move_once(&mut snake, SnakeDirection::Right);
+        move_once(&mut snake, SnakeDirection::Right);
+        assert_eq!(snake.pending_growth(), 0);
+        snake.grow(1);
+        assert_eq!(snake.pending_growth(), 1);
+        snake.shrink(2);
+        assert_eq!(snake.pending_growth(), 0);
+        assert_eq!(snake.queue.len(), 2);
+    }
+
+    #[test]
+    fn pending_growth_counts_the_members_left_behind_by_a_continuous_head() {
+        let spawn = Vec3::new(5f32, 5f32, 0f32);
+        let mut snake = SnakeState::new(spawn, MovementMode::Continuous, EdgeRule::default());
+        snake.grow(3);
+        snake.turn(SnakeDirection::Right);
+        snake.advance(SIMULATION_TIME_STEP);
+        assert_ne!(snake.position, spawn);
+        assert_eq!(snake.queue, vec![spawn; 3]);
+        assert_eq!(snake.pending_growth(), 3);
+        while snake.position.x < spawn.x + 4f32 * QUEUE_SPACING {
+            snake.advance(SIMULATION_TIME_STEP);
+        }
+        assert_eq!(snake.pending_growth(), 0);
+    }
+
+    #[test]
+    fn reversed_snake_starts_from_its_tail() {
+        let mut snake = grid_snake(3);
+        for _ in 0..5 {
+            move_once(&mut snake, SnakeDirection::Right);
+        }
+        assert_eq!(snake.queue, vec![cell(45f32), cell(35f32), cell(25f32)]);
+        snake.reverse();
+        assert_eq!(snake.position, cell(25f32));
+        assert_eq!(snake.direction, Some(SnakeDirection::Left));
+        assert_eq!(snake.queue, vec![cell(35f32), cell(45f32), cell(55f32)]);
+        move_once(&mut snake, SnakeDirection::Left);
+        assert_eq!(snake.position, cell(15f32));
+        assert_eq!(snake.queue, vec![cell(25f32), cell(35f32), cell(45f32)]);
+    }
+
+    #[test]
+    fn reversed_snake_keeps_its_pending_growth_on_the_new_tail() {
+        let mut snake = grid_snake(3);
+        for _ in 0..5 {
+            move_once(&mut snake, SnakeDirection::Right);
+        }
+        snake.grow(2);
+        snake.reverse();
+        assert_eq!(snake.position, cell(25f32));
+        assert_eq!(snake.pending_growth(), 2);
+        assert_eq!(
+            snake.queue,
+            vec![
+                cell(35f32),
+                cell(45f32),
+                cell(55f32),
+                cell(55f32),
+                cell(55f32)
+            ]
+        );
+    }
+}
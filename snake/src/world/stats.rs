@@ -0,0 +1,99 @@
+use crate::components::prelude::Bonus;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::event::{CollisionEvent, GameEvent};
+
+/// What killed the snake.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DeathCause {
+    /// The snake hit a wall of the level.
+    Wall,
+    /// The snake hit a moving obstacle.
+    Obstacle,
+    /// The snake went through a solid screen edge.
+    ScreenEdge,
+    /// The snake bit its own queue.
+    Queue,
+    /// The snake hit the other snake of a versus game.
+    Rival,
+}
+
+impl fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Wall => write!(f, "wall"),
+            Self::Obstacle => write!(f, "obstacle"),
+            Self::ScreenEdge => write!(f, "screen edge"),
+            Self::Queue => write!(f, "own queue"),
+            Self::Rival => write!(f, "rival"),
+        }
+    }
+}
+
+/// Counts kept for each kind of bonus, in the order they first happened.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BonusCounts(Vec<(Bonus, u32)>);
+
+impl BonusCounts {
+    /// Counts the given kind of bonus the given number of times more.
+    pub fn add(&mut self, bonus: Bonus, count: u32) {
+        match self.0.iter_mut().find(|(kind, _)| *kind == bonus) {
+            Some((_, total)) => *total += count,
+            None => self.0.push((bonus, count)),
+        }
+    }
+
+    /// Returns the count of every kind of bonus counted at least once.
+    pub fn iter(&self) -> impl Iterator<Item = &(Bonus, u32)> {
+        self.0.iter()
+    }
+
+    /// Returns the count of the given kind of bonus.
+    pub fn get(&self, bonus: Bonus) -> u32 {
+        self.0
+            .iter()
+            .find(|(kind, _)| *kind == bonus)
+            .map(|(_, count)| *count)
+            .unwrap_or_default()
+    }
+
+    /// Returns the count of all the kinds of bonus.
+    pub fn total(&self) -> u32 {
+        self.0.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// What happened during a game, shown once it ended.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GameStats {
+    /// The time the snake moved for, in seconds.
+    pub duration: f32,
+    /// The distance travelled by the head, in pixels.
+    pub distance: f32,
+    /// The bonuses eaten, by kind.
+    pub bonuses_eaten: BonusCounts,
+    /// The extra bonuses that expired before the snake ate them, by kind.
+    pub bonuses_missed: BonusCounts,
+    /// The turns taken by the snake.
+    pub turns: u32,
+    /// The longest the queue has been.
+    pub max_length: usize,
+    /// What killed the snake, none while it is alive or if the player won.
+    pub death_cause: Option<DeathCause>,
+}
+
+impl GameStats {
+    /// Counts what the given events of a step tell.
+    pub fn record(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::Collision(CollisionEvent::Bonus(bonus)) => {
+                    self.bonuses_eaten.add(*bonus, 1)
+                }
+                GameEvent::BonusExpired(bonus) => self.bonuses_missed.add(*bonus, 1),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::{math::Vec3, render::color::Color};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::scoring::ScoreBreakdown;
+use super::snake::SnakeState;
+
+/// One of the players sharing the keyboard in a versus game.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Player {
+    /// The player of the arrow keys, or of the only snake.
+    #[default]
+    One,
+    /// The player of the WASD or ZQSD keys.
+    Two,
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::One => write!(f, "Player 1"),
+            Self::Two => write!(f, "Player 2"),
+        }
+    }
+}
+
+impl Player {
+    /// Returns the color of the player's snake head.
+    pub fn head_color(&self) -> Color {
+        match self {
+            Self::One => Color::WHITE,
+            Self::Two => Color::ORANGE,
+        }
+    }
+
+    /// Returns the color of the player's snake queue.
+    pub fn queue_color(&self) -> Color {
+        match self {
+            Self::One => Color::GRAY,
+            Self::Two => Color::rgb(0.6, 0.35, 0f32),
+        }
+    }
+}
+
+/// How a versus game ended.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VersusOutcome {
+    /// The given player won.
+    Winner(Player),
+    /// Both snakes died at the same time with the same length, or the board
+    /// got full with both players on the same score.
+    Draw,
+}
+
+impl fmt::Display for VersusOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Winner(player) => write!(f, "{} wins !", player),
+            Self::Draw => write!(f, "Draw !"),
+        }
+    }
+}
+
+impl VersusOutcome {
+    /// Returns the outcome favoring the player with the highest value, a draw
+    /// on equal values.
+    pub fn by_highest<T: PartialOrd>(one: T, two: T) -> Self {
+        if two < one {
+            Self::Winner(Player::One)
+        } else if one < two {
+            Self::Winner(Player::Two)
+        } else {
+            Self::Draw
+        }
+    }
+}
+
+/// The snake of the second player of a versus game, along with its score.
+#[derive(Debug, Clone)]
+pub struct Rival {
+    /// The snake.
+    pub snake: SnakeState,
+    /// The score, raised the same way as the first player's one.
+    pub score: u32,
+    /// The detail of the score.
+    pub score_breakdown: ScoreBreakdown,
+    /// The center of the portal the head came out of, it can't go through it
+    /// again before leaving it.
+    pub(super) portal_exit: Option<Vec3>,
+}
+
+impl Rival {
+    /// Creates the second snake, with an empty score multiplied by the given factor.
+    pub fn new(snake: SnakeState, multiplier: f32) -> Self {
+        Self {
+            snake,
+            score: 0,
+            score_breakdown: ScoreBreakdown::new(multiplier),
+            portal_exit: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highest_value_wins() {
+        assert_eq!(
+            VersusOutcome::by_highest(3, 2),
+            VersusOutcome::Winner(Player::One)
+        );
+        assert_eq!(
+            VersusOutcome::by_highest(2, 3),
+            VersusOutcome::Winner(Player::Two)
+        );
+        assert_eq!(VersusOutcome::by_highest(2, 2), VersusOutcome::Draw);
+    }
+}